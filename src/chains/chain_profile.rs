@@ -1,15 +1,122 @@
-use std::path::PathBuf;
+use std::{
+    path::PathBuf,
+    process::{Command, Stdio},
+};
 
 use serde_json::Value;
 
 use crate::cosmos::{
+    code_info::{CodeInfoResponse, ContractInfoResponse, ListCodeResponse},
     keys_show::KeysShowResponse,
     tx_query::TxQueryResponse,
 };
 use crate::commands::config::NetworkConfig;
-use crate::utils::project_config::{Network, ProjectConfig};
+use crate::utils::{
+    command_util::CommandWithInput,
+    offline_tx,
+    project_config::{InstantiatePermission, Network, ProjectConfig},
+    wasm_check::ChainCapabilities,
+};
 use crate::WarpError;
 
+/// How a contract is instantiated, besides its code and message
+#[derive(Default)]
+pub struct InstantiateOptions<'a> {
+    pub label: &'a str,
+    /// `None` instantiates the contract without an admin
+    pub admin: Option<&'a str>,
+    /// Funds sent to the contract, e.g. `1000ujuno`
+    pub coins: Option<String>,
+    /// Instantiates with `instantiate2`, for a predictable address
+    pub salt: Option<&'a str>,
+}
+
+impl InstantiateOptions<'_> {
+    /// Arguments of `tx wasm instantiate` (`instantiate2` with a salt), without the sender
+    pub fn wasm_args(&self, code_id: &str, init_msg: &str) -> Vec<String> {
+        let mut args = match self.salt {
+            Some(salt) => vec![
+                "wasm".to_owned(),
+                "instantiate2".to_owned(),
+                code_id.to_owned(),
+                init_msg.to_owned(),
+                hex::encode(salt.as_bytes()),
+                "--hex".to_owned(),
+            ],
+            None => vec![
+                "wasm".to_owned(),
+                "instantiate".to_owned(),
+                code_id.to_owned(),
+                init_msg.to_owned(),
+            ],
+        };
+        args.extend([
+            "--label".to_owned(),
+            self.label.to_owned(),
+            "--amount".to_owned(),
+            self.coins.clone().unwrap_or_default(),
+        ]);
+        match self.admin {
+            Some(admin) => args.extend(["--admin".to_owned(), admin.to_owned()]),
+            None => args.push("--no-admin".to_owned()),
+        }
+        args
+    }
+}
+
+/// Sends `<cli> tx <args>` from `from` and waits for it to be included, or writes the unsigned
+/// transaction when the config is set to generate only
+pub fn send_tx<P: ChainProfile + ?Sized>(
+    profile: &P,
+    args: &[String],
+    from: &str,
+    password: Option<&str>,
+    config: &ProjectConfig,
+) -> Result<TxQueryResponse, WarpError> {
+    let mut tx = Command::new(profile.get_executable_name());
+    tx.arg("tx")
+        .args(args)
+        .args(["--from", from])
+        .args(profile.get_common_cli_args(true, true, false, config))
+        .stdout(Stdio::piped())
+        .stdin(if password.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::inherit()
+        });
+    let json_data = match password {
+        Some(pass) => tx.call_process_with_input(pass)?.stdout,
+        None => tx.output()?.stdout,
+    };
+    if let Some(path) = &config.generate_only {
+        return offline_tx::save_unsigned(path, &json_data);
+    }
+    let response: TxQueryResponse = serde_json::from_slice(json_data.as_slice())?;
+    if response.code != 0 {
+        return Err(WarpError::TxFailed(response.txhash, response.raw_log));
+    }
+    profile.query_tx(&response.txhash, config)
+}
+
+/// `set-contract-admin`, or `clear-contract-admin` without a new admin, of the module that
+/// manages contracts (`wasm`, or `compute` on Secret)
+pub fn admin_tx<P: ChainProfile + ?Sized>(
+    profile: &P,
+    module: &str,
+    contract_address: &str,
+    new_admin: Option<&str>,
+    from: &str,
+    password: Option<&str>,
+    config: &ProjectConfig,
+) -> Result<TxQueryResponse, WarpError> {
+    let args = match new_admin {
+        Some(admin) => vec![module, "set-contract-admin", contract_address, admin],
+        None => vec![module, "clear-contract-admin", contract_address],
+    };
+    let args: Vec<String> = args.into_iter().map(|x| x.to_owned()).collect();
+    send_tx(profile, &args, from, password, config)
+}
+
 pub trait ChainProfile {
     fn get_executable_name(&self) -> String;
     fn get_profile_name(&self) -> String;
@@ -30,17 +137,16 @@ pub trait ChainProfile {
         &self,
         contract: &str,
         from: &str,
+        permission: Option<&InstantiatePermission>,
         password: Option<&str>,
         config: &ProjectConfig,
     ) -> Result<TxQueryResponse, WarpError>;
     fn instantiate_contract(
         &self,
         code_id: &str,
-        from: &str,
-        admin: &str,
-        label: &str,
         init_msg: &str,
-        coins: Option<String>,
+        from: &str,
+        options: &InstantiateOptions,
         password: Option<&str>,
         config: &ProjectConfig,
     ) -> Result<TxQueryResponse, WarpError>;
@@ -61,14 +167,120 @@ pub trait ChainProfile {
         password: Option<&str>,
         config: &ProjectConfig,
     ) -> Result<TxQueryResponse, WarpError>;
+    fn update_admin(
+        &self,
+        contract_address: &str,
+        new_admin: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError>;
+    fn clear_admin(
+        &self,
+        contract_address: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError>;
+    /// Waits for the transaction to be included in a block (see `utils::confirmation`)
     fn query_tx(&self, tx_hash: &str, config: &ProjectConfig)
         -> Result<TxQueryResponse, WarpError>;
+    fn query_block_height(&self, config: &ProjectConfig) -> Result<u64, WarpError>;
     fn query_contract_smart(
         &self,
         contract: &str,
         query: &str,
         config: &ProjectConfig,
     ) -> Result<Value, WarpError>;
+    fn query_contract_info(
+        &self,
+        contract: &str,
+        config: &ProjectConfig,
+    ) -> Result<ContractInfoResponse, WarpError>;
+    fn query_code_info(
+        &self,
+        code_id: &str,
+        config: &ProjectConfig,
+    ) -> Result<CodeInfoResponse, WarpError>;
+    /// Returns the contract using the label if the chain enforces unique labels
+    fn query_contract_by_label(
+        &self,
+        label: &str,
+        config: &ProjectConfig,
+    ) -> Result<Option<String>, WarpError>;
+    /// Returns the newest code ID with the given checksum (hex)
+    fn find_code_by_checksum(
+        &self,
+        checksum: &str,
+        config: &ProjectConfig,
+    ) -> Result<Option<String>, WarpError> {
+        const PAGE_SIZE: usize = 100;
+        // `--page-key` takes the raw key bytes, which the base64 `next_key` of the binary code
+        // ID keys can't be passed as, so the pages are requested by offset instead
+        let mut offset = 0;
+        loop {
+            let cmd = Command::new(self.get_executable_name())
+                .args(vec!["q", "wasm", "list-code", "--reverse"])
+                .args(vec!["--limit".to_owned(), PAGE_SIZE.to_string()])
+                .args(vec!["--offset".to_owned(), offset.to_string()])
+                .args(self.get_common_cli_args(false, true, false, config))
+                .stdin(Stdio::inherit())
+                .output()?;
+            if !cmd.status.success() {
+                let msg = String::from_utf8(cmd.stderr)?;
+                return Err(WarpError::UnderlyingCliError(msg));
+            }
+            let response: ListCodeResponse = serde_json::from_slice(cmd.stdout.as_slice())?;
+            let page_size = response.code_infos.len();
+            // Newest first, so the first page with a match has the newest code
+            let code_id = response
+                .code_infos
+                .into_iter()
+                .filter(|x| x.checksum.eq_ignore_ascii_case(checksum))
+                .max_by_key(|x| x.code_id.parse::<u64>().unwrap_or_default())
+                .map(|x| x.code_id);
+            let has_next = response
+                .pagination
+                .and_then(|x| x.next_key)
+                .is_some_and(|x| !x.is_empty());
+            if code_id.is_some() || !has_next || page_size == 0 {
+                return Ok(code_id);
+            }
+            offset += page_size;
+        }
+    }
+    /// Type URL of the message that uploads code in a governance proposal
+    fn store_code_msg_type(&self) -> Result<String, WarpError> {
+        Ok("/cosmwasm.wasm.v1.MsgStoreCode".to_owned())
+    }
+    /// Bech32 prefix of account addresses, `None` if the native keystore can't derive the keys
+    /// of the profile's chains
+    fn address_prefix(&self) -> Option<String> {
+        None
+    }
+    /// BIP-44 coin type of keys derived by the native keystore
+    fn coin_type(&self) -> u32 {
+        118
+    }
+    /// Returns the status of a governance proposal, e.g. `PROPOSAL_STATUS_PASSED`
+    fn query_proposal_status(
+        &self,
+        proposal_id: &str,
+        config: &ProjectConfig,
+    ) -> Result<String, WarpError>;
+    /// Returns the minimum gas price if the chain exposes it, e.g. `0.025ujuno`
+    fn query_gas_price(&self, config: &ProjectConfig) -> Result<Option<String>, WarpError>;
+    /// Simulates executing a contract and returns the gas it uses
+    fn simulate_execute(
+        &self,
+        contract_address: &str,
+        msg: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<u64, WarpError>;
+    /// Capabilities and the maximum contract size the profile's chains accept
+    fn chain_capabilities(&self) -> ChainCapabilities;
     fn init_project(&self, dir: &PathBuf) -> Result<(), WarpError>;
     fn new_contract(
         &self,
@@ -80,6 +292,9 @@ pub trait ChainProfile {
     fn get_node_docker_command(&self, container: Option<String>, config: &ProjectConfig) -> String;
 
     fn network_params(&self, network_config: &NetworkConfig) -> Network;
-    fn get_initialized_address(&self, tx: &TxQueryResponse) -> String;
+    /// The code ID from the events of a store transaction
+    fn get_stored_code_id(&self, tx: &TxQueryResponse) -> Result<String, WarpError>;
+    /// The contract address from the events of an instantiate transaction
+    fn get_initialized_address(&self, tx: &TxQueryResponse) -> Result<String, WarpError>;
     fn init_frontend(&self, dir: &PathBuf) -> Result<(), WarpError>;
 }
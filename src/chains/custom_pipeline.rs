@@ -1,16 +1,16 @@
 use std::{
-    fs::File, io::Write, net, path::PathBuf, process::{Command, Stdio}, time::Duration
+    fs::File, io::Write, net, path::PathBuf, process::{Command, Stdio}
 };
 
 use serde_json::Value;
 
 use crate::{
-    commands::config::NetworkConfig, cosmos::{keys_show::KeysShowResponse, tx_query::TxQueryResponse}, error::WarpError, toolchains::pipeline::Pipeline, utils::{file_util, project_config::Network}
+    commands::config::NetworkConfig, cosmos::{code_info::{CodeInfoResponse, ContractInfoResponse}, events, keys_show::KeysShowResponse, proposal, tx_query::TxQueryResponse}, error::WarpError, toolchains::pipeline::Pipeline, utils::{file_util, project_config::Network}
 };
 
-use crate::utils::{command_util::CommandWithInput, project_config::ProjectConfig};
+use crate::utils::{command_util::CommandWithInput, confirmation, gas, offline_tx, project_config::{InstantiatePermission, ProjectConfig}, wasm_check::{ChainCapabilities, DEFAULT_MAX_WASM_SIZE}};
 
-use super::chain_profile::ChainProfile;
+use super::chain_profile::{admin_tx, send_tx, ChainProfile, InstantiateOptions};
 
 pub struct CustomPipelineProfile {
     pub pipeline: Pipeline,
@@ -42,6 +42,14 @@ impl ChainProfile for CustomPipelineProfile {
         args.extend_from_slice(self.pipeline.config.cli_args.split_whitespace().collect::<Vec<&str>>().as_slice().iter().map(|s| s.to_string()).collect::<Vec<String>>().as_slice());
         if tx {
             args.extend_from_slice(self.pipeline.config.cli_args_tx.split_whitespace().collect::<Vec<&str>>().as_slice().iter().map(|s| s.to_string()).collect::<Vec<String>>().as_slice());
+            // The pipeline's own gas flags are used unless Warp.toml configures gas
+            if config.network.gas.is_some() {
+                args.extend(config.gas_cli_args(store));
+            }
+            args.extend(config.keyring_cli_args());
+            if config.generate_only.is_some() {
+                args.push("--generate-only".to_string());
+            }
         }
         if network {
             args.extend_from_slice(self.pipeline.config.cli_args_network.split_whitespace().collect::<Vec<&str>>().as_slice().iter().map(|s| s.to_string()).collect::<Vec<String>>().as_slice());
@@ -59,6 +67,7 @@ impl ChainProfile for CustomPipelineProfile {
         let mut tx = Command::new(self.get_executable_name());
         tx.args(vec!["keys", "show", account_id])
             .args(self.get_common_cli_args(false, false, false, config))
+            .args(config.keyring_cli_args())
             .stdout(Stdio::piped())
             .stdin(if password.is_some() {
                 Stdio::piped()
@@ -82,11 +91,13 @@ impl ChainProfile for CustomPipelineProfile {
         &self,
         contract: &str,
         from: &str,
+        permission: Option<&InstantiatePermission>,
         password: Option<&str>,
         config: &ProjectConfig,
     ) -> Result<TxQueryResponse, WarpError> {
         let mut tx = Command::new(self.get_executable_name());
         tx.args(vec!["tx", "wasm", "store", contract, "--from", from])
+            .args(permission.map(|x| x.cli_args()).unwrap_or_default())
             .args(self.get_common_cli_args(true, true, true, config))
             .stdout(Stdio::piped())
             .current_dir(ProjectConfig::find_project_root()?)
@@ -104,6 +115,9 @@ impl ChainProfile for CustomPipelineProfile {
             let bytes = out.stdout.clone();
             json_data = bytes;
         }
+        if let Some(path) = &config.generate_only {
+            return offline_tx::save_unsigned(path, &json_data);
+        }
         let response: TxQueryResponse = serde_json::from_slice(json_data.as_slice())?;
         if response.code != 0 {
             return Err(WarpError::TxFailed(response.txhash, response.raw_log));
@@ -115,52 +129,13 @@ impl ChainProfile for CustomPipelineProfile {
     fn instantiate_contract(
         &self,
         code_id: &str,
-        from: &str,
-        admin: &str,
-        label: &str,
         init_msg: &str,
-        coins: Option<String>,
+        from: &str,
+        options: &InstantiateOptions,
         password: Option<&str>,
         config: &ProjectConfig,
     ) -> Result<TxQueryResponse, WarpError> {
-        let mut tx = Command::new(self.get_executable_name());
-        tx.args(vec![
-            "tx",
-            "wasm",
-            "instantiate",
-            code_id,
-            init_msg,
-            "--from",
-            from,
-            "--label",
-            label,
-            "--amount",
-            &coins.unwrap_or_default(),
-            "--admin",
-            admin,
-        ])
-        .args(self.get_common_cli_args(true, true, false, config))
-        .stdout(Stdio::piped())
-        .stdin(if password.is_some() {
-            Stdio::piped()
-        } else {
-            Stdio::inherit()
-        });
-        let json_data: Vec<u8>;
-        if let Some(pass) = password {
-            let out = tx.call_process_with_input(pass)?;
-            json_data = out.stdout;
-        } else {
-            let out = tx.output()?;
-            let bytes = out.stdout.clone();
-            json_data = bytes;
-        }
-        let response: TxQueryResponse = serde_json::from_slice(json_data.as_slice())?;
-        if response.code != 0 {
-            return Err(WarpError::TxFailed(response.txhash, response.raw_log));
-        }
-        let response = self.query_tx(&response.txhash, config)?;
-        Ok(response)
+        send_tx(self, &options.wasm_args(code_id, init_msg), from, password, config)
     }
 
     fn execute_contract(
@@ -197,6 +172,9 @@ impl ChainProfile for CustomPipelineProfile {
             let bytes = out.stdout.clone();
             json_data = bytes;
         }
+        if let Some(path) = &config.generate_only {
+            return offline_tx::save_unsigned(path, &json_data);
+        }
         let response: TxQueryResponse = serde_json::from_slice(json_data.as_slice())?;
         if response.code != 0 {
             return Err(WarpError::TxFailed(response.txhash, response.raw_log));
@@ -241,6 +219,9 @@ impl ChainProfile for CustomPipelineProfile {
             let bytes = out.stdout.clone();
             json_data = bytes;
         }
+        if let Some(path) = &config.generate_only {
+            return offline_tx::save_unsigned(path, &json_data);
+        }
         let response: TxQueryResponse = serde_json::from_slice(json_data.as_slice())?;
         if response.code != 0 {
             return Err(WarpError::TxFailed(response.txhash, response.raw_log));
@@ -249,33 +230,57 @@ impl ChainProfile for CustomPipelineProfile {
         Ok(response)
     }
 
-    // TODO: Make this ugly thing go away once a better solution is confirmed to be working
+    fn update_admin(
+        &self,
+        contract_address: &str,
+        new_admin: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError> {
+        admin_tx(self, "wasm", contract_address, Some(new_admin), from, password, config)
+    }
+
+    fn clear_admin(
+        &self,
+        contract_address: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError> {
+        admin_tx(self, "wasm", contract_address, None, from, password, config)
+    }
+
     fn query_tx(
         &self,
         tx_hash: &str,
         config: &ProjectConfig,
     ) -> Result<TxQueryResponse, WarpError> {
-        let mut retries = 10;
-        loop {
-            let cmd = Command::new(self.get_executable_name())
-                .args(vec!["q", "tx", tx_hash])
-                .args(self.get_common_cli_args(false, true, false, config))
-                .stdin(Stdio::inherit())
-                .output()?;
-            let tx = cmd.stdout;
-            let stderr = cmd.stderr;
-            if !stderr.is_empty() && retries > 0 {
-                // crude but will do for beta
-                retries -= 1;
-                std::thread::sleep(Duration::from_millis(1958));
-                continue;
-            }
-            let response: TxQueryResponse = serde_json::from_slice(tx.as_slice())?;
-            if response.code != 0 {
-                return Err(WarpError::TxFailed(response.txhash, response.raw_log));
-            }
-            return Ok(response);
+        let response = confirmation::wait_for_tx(
+            tx_hash,
+            config,
+            || {
+                let cmd = Command::new(self.get_executable_name())
+                    .args(vec!["q", "tx", tx_hash])
+                    .args(self.get_common_cli_args(false, true, false, config))
+                    .stdin(Stdio::inherit())
+                    .output()?;
+                confirmation::parse_tx_lookup(&cmd)
+            },
+            || self.query_block_height(config),
+        )?;
+        if response.code != 0 {
+            return Err(WarpError::TxFailed(response.txhash, response.raw_log));
         }
+        Ok(response)
+    }
+
+    fn query_block_height(&self, config: &ProjectConfig) -> Result<u64, WarpError> {
+        let cmd = Command::new(self.get_executable_name())
+            .args(vec!["status", "--node", &config.network.rpc_url])
+            .stdin(Stdio::inherit())
+            .output()?;
+        confirmation::parse_block_height(&cmd)
     }
 
     fn query_contract_smart(
@@ -305,6 +310,130 @@ impl ChainProfile for CustomPipelineProfile {
         Ok(response)
     }
 
+    fn query_contract_info(
+        &self,
+        contract: &str,
+        config: &ProjectConfig,
+    ) -> Result<ContractInfoResponse, WarpError> {
+        let cmd = Command::new(self.get_executable_name())
+            .args(vec!["q", "wasm", "contract", contract])
+            .args(self.get_common_cli_args(false, true, false, config))
+            .stdin(Stdio::inherit())
+            .output()?;
+        if !cmd.status.success() {
+            let msg = String::from_utf8(cmd.stderr)?;
+            return Err(WarpError::UnderlyingCliError(msg));
+        }
+        let response: ContractInfoResponse = serde_json::from_slice(cmd.stdout.as_slice())?;
+        Ok(response)
+    }
+
+    fn query_code_info(
+        &self,
+        code_id: &str,
+        config: &ProjectConfig,
+    ) -> Result<CodeInfoResponse, WarpError> {
+        let cmd = Command::new(self.get_executable_name())
+            .args(vec!["q", "wasm", "code-info", code_id])
+            .args(self.get_common_cli_args(false, true, false, config))
+            .stdin(Stdio::inherit())
+            .output()?;
+        if !cmd.status.success() {
+            let msg = String::from_utf8(cmd.stderr)?;
+            return Err(WarpError::UnderlyingCliError(msg));
+        }
+        let response: CodeInfoResponse = serde_json::from_slice(cmd.stdout.as_slice())?;
+        Ok(response)
+    }
+
+    fn query_contract_by_label(
+        &self,
+        _label: &str,
+        _config: &ProjectConfig,
+    ) -> Result<Option<String>, WarpError> {
+        // Labels are not unique in the wasm module
+        Ok(None)
+    }
+
+    fn query_proposal_status(
+        &self,
+        proposal_id: &str,
+        config: &ProjectConfig,
+    ) -> Result<String, WarpError> {
+        let cmd = Command::new(self.get_executable_name())
+            .args(vec!["q", "gov", "proposal", proposal_id])
+            .args(self.get_common_cli_args(false, true, false, config))
+            .stdin(Stdio::inherit())
+            .output()?;
+        if !cmd.status.success() {
+            let msg = String::from_utf8(cmd.stderr)?;
+            return Err(WarpError::UnderlyingCliError(msg));
+        }
+        let response: Value = serde_json::from_slice(cmd.stdout.as_slice())?;
+        proposal::proposal_status(&response)
+            .ok_or_else(|| WarpError::UnderlyingCliError(String::from_utf8_lossy(&cmd.stdout).to_string()))
+    }
+
+    fn query_gas_price(&self, _config: &ProjectConfig) -> Result<Option<String>, WarpError> {
+        // Pipelines configure their gas prices in `cli_args_tx`
+        Ok(None)
+    }
+
+    fn simulate_execute(
+        &self,
+        contract_address: &str,
+        msg: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<u64, WarpError> {
+        let mut tx = Command::new(self.get_executable_name());
+        tx.args(vec![
+            "tx",
+            "wasm",
+            "execute",
+            contract_address,
+            msg,
+            "--from",
+            from,
+            "--dry-run",
+            "--gas",
+            "auto",
+        ])
+        .args(self.get_common_cli_args(false, true, false, config))
+        .args(vec!["--chain-id", &config.network.chain_id])
+        .args(config.keyring_cli_args())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .stdin(if password.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::inherit()
+        });
+        let out = match password {
+            Some(pass) => tx.call_process_with_input(pass)?,
+            None => tx.output()?,
+        };
+        gas::parse_gas_estimate(&out)
+    }
+
+    fn chain_capabilities(&self) -> ChainCapabilities {
+        let config = &self.pipeline.config;
+        let defaults = ChainCapabilities::new(
+            &["iterator", "staking", "stargate"],
+            "cosmwasm_2_0",
+            DEFAULT_MAX_WASM_SIZE,
+        );
+        ChainCapabilities {
+            capabilities: config
+                .capabilities
+                .as_ref()
+                .map(|x| x.iter().cloned().collect())
+                .unwrap_or(defaults.capabilities),
+            max_wasm_size: config.max_wasm_size.unwrap_or(defaults.max_wasm_size),
+        }
+    }
+
     fn init_project(&self, dir: &PathBuf) -> Result<(), WarpError> {
         println!("Initializing new workspace...");
         let cmd = Command::new("git")
@@ -401,6 +530,7 @@ impl ChainProfile for CustomPipelineProfile {
                 rpc_url: "https://juno-rpc.polkachu.com:443".to_owned(),
                 denom: "ujuno".to_owned(),
                 gas_prices: Some("0.002ujuno".to_owned()),
+                ..Default::default()
             },
             NetworkConfig::Testnet => Network {
                 profile: self.get_profile_name(),
@@ -408,6 +538,7 @@ impl ChainProfile for CustomPipelineProfile {
                 rpc_url: "https://testnet.sentry.tm.injective.network:443".to_owned(),
                 denom: "ujunox".to_owned(),
                 gas_prices: Some("0.0002ujunox".to_owned()),
+                ..Default::default()
             },
             NetworkConfig::Local => Network {
                 profile: self.get_profile_name(),
@@ -415,23 +546,17 @@ impl ChainProfile for CustomPipelineProfile {
                 rpc_url: "http://localhost:26657".to_owned(), // TODO: Add local node URL
                 denom: "ujunox".to_owned(),
                 gas_prices: Some("0.000001ujunox".to_owned()),
+                ..Default::default()
             },
         }
     }
 
-    fn get_initialized_address(&self, tx: &TxQueryResponse) -> String {
-        
-        tx.logs
-            .first()
-            .unwrap()
-            .events
-            .get(0)
-            .unwrap()
-            .attributes
-            .get(0)
-            .unwrap()
-            .value
-            .clone()
+    fn get_stored_code_id(&self, tx: &TxQueryResponse) -> Result<String, WarpError> {
+        events::code_id(tx)
+    }
+
+    fn get_initialized_address(&self, tx: &TxQueryResponse) -> Result<String, WarpError> {
+        events::contract_address(tx)
     }
 
     fn init_frontend(&self, _dir: &PathBuf) -> Result<(), WarpError> {
@@ -3,23 +3,25 @@ use std::{
     io::Write,
     path::PathBuf,
     process::{Command, Stdio},
-    time::Duration,
 };
 
 use serde_json::Value;
 
 use crate::{
     commands::config::NetworkConfig,
-    cosmos::{keys_show::KeysShowResponse, tx_query::TxQueryResponse},
+    cosmos::{code_info::{CodeInfoResponse, ContractInfoResponse}, events, keys_show::KeysShowResponse, proposal, tx_query::TxQueryResponse},
     error::WarpError,
     utils::{file_util, project_config::Network},
 };
 
-use crate::utils::{command_util::CommandWithInput, project_config::ProjectConfig};
+use crate::utils::{command_util::CommandWithInput, confirmation, gas, offline_tx, project_config::{InstantiatePermission, ProjectConfig}, wasm_check::{ChainCapabilities, DEFAULT_MAX_WASM_SIZE}};
 
-use super::chain_profile::ChainProfile;
+use super::chain_profile::{admin_tx, send_tx, ChainProfile, InstantiateOptions};
 
-pub struct JunoProfile;
+#[derive(Default)]
+pub struct JunoProfile {
+    gas_prices: gas::GasPriceCache,
+}
 
 impl JunoProfile {}
 
@@ -32,6 +34,10 @@ impl ChainProfile for JunoProfile {
         "juno".to_owned()
     }
 
+    fn address_prefix(&self) -> Option<String> {
+        Some("juno".to_owned())
+    }
+
     fn get_common_cli_args<'a, 'b>(
         &self,
         tx: bool,
@@ -53,22 +59,21 @@ impl ChainProfile for JunoProfile {
                 "-y".to_string(),
                 "-b".to_string(),
                 "sync".to_string(),
-                "--gas".to_string(),
-                "auto".to_string(),
-                "--gas-adjustment".to_string(),
-                if store {
-                    "2".to_string()
-                } else {
-                    "1.4".to_string()
-                },
                 "--gas-prices".to_string(),
-                config
-                    .network
-                    .gas_prices
-                    .clone()
-                    .unwrap_or("0.00025uxion".to_owned()),
+                self.gas_prices.resolve(config, || {
+                    // Falls back to the globalfee minimum of Juno's mainnet
+                    self.query_gas_price(config)
+                        .ok()
+                        .flatten()
+                        .unwrap_or(format!("0.075{}", config.network.denom))
+                }),
             ];
             args.append(&mut tx_args);
+            args.extend(config.gas_cli_args(store));
+            args.extend(config.keyring_cli_args());
+            if config.generate_only.is_some() {
+                args.push("--generate-only".to_string());
+            }
         }
         args
     }
@@ -82,6 +87,7 @@ impl ChainProfile for JunoProfile {
         let mut tx = Command::new(self.get_executable_name());
         tx.args(vec!["keys", "show", account_id])
             .args(self.get_common_cli_args(false, false, false, config))
+            .args(config.keyring_cli_args())
             .stdout(Stdio::piped())
             .stdin(if password.is_some() {
                 Stdio::piped()
@@ -105,11 +111,13 @@ impl ChainProfile for JunoProfile {
         &self,
         contract: &str,
         from: &str,
+        permission: Option<&InstantiatePermission>,
         password: Option<&str>,
         config: &ProjectConfig,
     ) -> Result<TxQueryResponse, WarpError> {
         let mut tx = Command::new(self.get_executable_name());
         tx.args(vec!["tx", "wasm", "store", contract, "--from", from])
+            .args(permission.map(|x| x.cli_args()).unwrap_or_default())
             .args(self.get_common_cli_args(true, true, true, config))
             .stdout(Stdio::piped())
             .current_dir(ProjectConfig::find_project_root()?)
@@ -127,6 +135,9 @@ impl ChainProfile for JunoProfile {
             let bytes = out.stdout.clone();
             json_data = bytes;
         }
+        if let Some(path) = &config.generate_only {
+            return offline_tx::save_unsigned(path, &json_data);
+        }
         let response: TxQueryResponse = serde_json::from_slice(json_data.as_slice())?;
         if response.code != 0 {
             return Err(WarpError::TxFailed(response.txhash, response.raw_log));
@@ -138,52 +149,13 @@ impl ChainProfile for JunoProfile {
     fn instantiate_contract(
         &self,
         code_id: &str,
-        from: &str,
-        admin: &str,
-        label: &str,
         init_msg: &str,
-        coins: Option<String>,
+        from: &str,
+        options: &InstantiateOptions,
         password: Option<&str>,
         config: &ProjectConfig,
     ) -> Result<TxQueryResponse, WarpError> {
-        let mut tx = Command::new(self.get_executable_name());
-        tx.args(vec![
-            "tx",
-            "wasm",
-            "instantiate",
-            code_id,
-            init_msg,
-            "--from",
-            from,
-            "--label",
-            label,
-            "--amount",
-            &coins.unwrap_or_default(),
-            "--admin",
-            admin,
-        ])
-        .args(self.get_common_cli_args(true, true, false, config))
-        .stdout(Stdio::piped())
-        .stdin(if password.is_some() {
-            Stdio::piped()
-        } else {
-            Stdio::inherit()
-        });
-        let json_data: Vec<u8>;
-        if let Some(pass) = password {
-            let out = tx.call_process_with_input(pass)?;
-            json_data = out.stdout;
-        } else {
-            let out = tx.output()?;
-            let bytes = out.stdout.clone();
-            json_data = bytes;
-        }
-        let response: TxQueryResponse = serde_json::from_slice(json_data.as_slice())?;
-        if response.code != 0 {
-            return Err(WarpError::TxFailed(response.txhash, response.raw_log));
-        }
-        let response = self.query_tx(&response.txhash, config)?;
-        Ok(response)
+        send_tx(self, &options.wasm_args(code_id, init_msg), from, password, config)
     }
 
     fn execute_contract(
@@ -220,6 +192,9 @@ impl ChainProfile for JunoProfile {
             let bytes = out.stdout.clone();
             json_data = bytes;
         }
+        if let Some(path) = &config.generate_only {
+            return offline_tx::save_unsigned(path, &json_data);
+        }
         let response: TxQueryResponse = serde_json::from_slice(json_data.as_slice())?;
         if response.code != 0 {
             return Err(WarpError::TxFailed(response.txhash, response.raw_log));
@@ -264,6 +239,9 @@ impl ChainProfile for JunoProfile {
             let bytes = out.stdout.clone();
             json_data = bytes;
         }
+        if let Some(path) = &config.generate_only {
+            return offline_tx::save_unsigned(path, &json_data);
+        }
         let response: TxQueryResponse = serde_json::from_slice(json_data.as_slice())?;
         if response.code != 0 {
             return Err(WarpError::TxFailed(response.txhash, response.raw_log));
@@ -272,33 +250,57 @@ impl ChainProfile for JunoProfile {
         Ok(response)
     }
 
-    // TODO: Make this ugly thing go away once a better solution is confirmed to be working
+    fn update_admin(
+        &self,
+        contract_address: &str,
+        new_admin: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError> {
+        admin_tx(self, "wasm", contract_address, Some(new_admin), from, password, config)
+    }
+
+    fn clear_admin(
+        &self,
+        contract_address: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError> {
+        admin_tx(self, "wasm", contract_address, None, from, password, config)
+    }
+
     fn query_tx(
         &self,
         tx_hash: &str,
         config: &ProjectConfig,
     ) -> Result<TxQueryResponse, WarpError> {
-        let mut retries = 10;
-        loop {
-            let cmd = Command::new(self.get_executable_name())
-                .args(vec!["q", "tx", tx_hash])
-                .args(self.get_common_cli_args(false, true, false, config))
-                .stdin(Stdio::inherit())
-                .output()?;
-            let tx = cmd.stdout;
-            let stderr = cmd.stderr;
-            if !stderr.is_empty() && retries > 0 {
-                // crude but will do for beta
-                retries -= 1;
-                std::thread::sleep(Duration::from_millis(1958));
-                continue;
-            }
-            let response: TxQueryResponse = serde_json::from_slice(tx.as_slice())?;
-            if response.code != 0 {
-                return Err(WarpError::TxFailed(response.txhash, response.raw_log));
-            }
-            return Ok(response);
+        let response = confirmation::wait_for_tx(
+            tx_hash,
+            config,
+            || {
+                let cmd = Command::new(self.get_executable_name())
+                    .args(vec!["q", "tx", tx_hash])
+                    .args(self.get_common_cli_args(false, true, false, config))
+                    .stdin(Stdio::inherit())
+                    .output()?;
+                confirmation::parse_tx_lookup(&cmd)
+            },
+            || self.query_block_height(config),
+        )?;
+        if response.code != 0 {
+            return Err(WarpError::TxFailed(response.txhash, response.raw_log));
         }
+        Ok(response)
+    }
+
+    fn query_block_height(&self, config: &ProjectConfig) -> Result<u64, WarpError> {
+        let cmd = Command::new(self.get_executable_name())
+            .args(vec!["status", "--node", &config.network.rpc_url])
+            .stdin(Stdio::inherit())
+            .output()?;
+        confirmation::parse_block_height(&cmd)
     }
 
     fn query_contract_smart(
@@ -328,6 +330,131 @@ impl ChainProfile for JunoProfile {
         Ok(response)
     }
 
+    fn query_contract_info(
+        &self,
+        contract: &str,
+        config: &ProjectConfig,
+    ) -> Result<ContractInfoResponse, WarpError> {
+        let cmd = Command::new(self.get_executable_name())
+            .args(vec!["q", "wasm", "contract", contract])
+            .args(self.get_common_cli_args(false, true, false, config))
+            .stdin(Stdio::inherit())
+            .output()?;
+        if !cmd.status.success() {
+            let msg = String::from_utf8(cmd.stderr)?;
+            return Err(WarpError::UnderlyingCliError(msg));
+        }
+        let response: ContractInfoResponse = serde_json::from_slice(cmd.stdout.as_slice())?;
+        Ok(response)
+    }
+
+    fn query_code_info(
+        &self,
+        code_id: &str,
+        config: &ProjectConfig,
+    ) -> Result<CodeInfoResponse, WarpError> {
+        let cmd = Command::new(self.get_executable_name())
+            .args(vec!["q", "wasm", "code-info", code_id])
+            .args(self.get_common_cli_args(false, true, false, config))
+            .stdin(Stdio::inherit())
+            .output()?;
+        if !cmd.status.success() {
+            let msg = String::from_utf8(cmd.stderr)?;
+            return Err(WarpError::UnderlyingCliError(msg));
+        }
+        let response: CodeInfoResponse = serde_json::from_slice(cmd.stdout.as_slice())?;
+        Ok(response)
+    }
+
+    fn query_contract_by_label(
+        &self,
+        _label: &str,
+        _config: &ProjectConfig,
+    ) -> Result<Option<String>, WarpError> {
+        // Labels are not unique in the wasm module
+        Ok(None)
+    }
+
+    fn query_proposal_status(
+        &self,
+        proposal_id: &str,
+        config: &ProjectConfig,
+    ) -> Result<String, WarpError> {
+        let cmd = Command::new(self.get_executable_name())
+            .args(vec!["q", "gov", "proposal", proposal_id])
+            .args(self.get_common_cli_args(false, true, false, config))
+            .stdin(Stdio::inherit())
+            .output()?;
+        if !cmd.status.success() {
+            let msg = String::from_utf8(cmd.stderr)?;
+            return Err(WarpError::UnderlyingCliError(msg));
+        }
+        let response: Value = serde_json::from_slice(cmd.stdout.as_slice())?;
+        proposal::proposal_status(&response)
+            .ok_or_else(|| WarpError::UnderlyingCliError(String::from_utf8_lossy(&cmd.stdout).to_string()))
+    }
+
+    fn query_gas_price(&self, config: &ProjectConfig) -> Result<Option<String>, WarpError> {
+        // Validators accept the minimum gas prices of the globalfee module
+        let cmd = Command::new(self.get_executable_name())
+            .args(vec!["q", "globalfee", "minimum-gas-prices"])
+            .args(self.get_common_cli_args(false, true, false, config))
+            .stdin(Stdio::inherit())
+            .output()?;
+        if !cmd.status.success() {
+            return Ok(None);
+        }
+        let response: Value = serde_json::from_slice(cmd.stdout.as_slice())?;
+        Ok(response["minimum_gas_prices"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|x| x["denom"] == config.network.denom.as_str())
+            .and_then(|x| Some(format!("{}{}", x["amount"].as_str()?, config.network.denom))))
+    }
+
+    fn simulate_execute(
+        &self,
+        contract_address: &str,
+        msg: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<u64, WarpError> {
+        let mut tx = Command::new(self.get_executable_name());
+        tx.args(vec![
+            "tx",
+            "wasm",
+            "execute",
+            contract_address,
+            msg,
+            "--from",
+            from,
+            "--dry-run",
+            "--gas",
+            "auto",
+        ])
+        .args(self.get_common_cli_args(false, true, false, config))
+        .args(vec!["--chain-id", &config.network.chain_id])
+        .args(config.keyring_cli_args())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .stdin(if password.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::inherit()
+        });
+        let out = match password {
+            Some(pass) => tx.call_process_with_input(pass)?,
+            None => tx.output()?,
+        };
+        gas::parse_gas_estimate(&out)
+    }
+
+    fn chain_capabilities(&self) -> ChainCapabilities {
+        ChainCapabilities::new(&["iterator", "staking", "stargate"], "cosmwasm_2_0", DEFAULT_MAX_WASM_SIZE)
+    }
+
     fn init_project(&self, dir: &PathBuf) -> Result<(), WarpError> {
         println!("Initializing new workspace...");
         let cmd = Command::new("git")
@@ -424,6 +551,7 @@ impl ChainProfile for JunoProfile {
                 rpc_url: "https://juno-rpc.polkachu.com:443".to_owned(),
                 denom: "ujuno".to_owned(),
                 gas_prices: Some("0.002ujuno".to_owned()),
+                ..Default::default()
             },
             NetworkConfig::Testnet => Network {
                 profile: self.get_profile_name(),
@@ -431,6 +559,7 @@ impl ChainProfile for JunoProfile {
                 rpc_url: "https://testnet.sentry.tm.injective.network:443".to_owned(),
                 denom: "ujunox".to_owned(),
                 gas_prices: Some("0.0002ujunox".to_owned()),
+                ..Default::default()
             },
             NetworkConfig::Local => Network {
                 profile: self.get_profile_name(),
@@ -438,23 +567,17 @@ impl ChainProfile for JunoProfile {
                 rpc_url: "http://localhost:26657".to_owned(), // TODO: Add local node URL
                 denom: "ujunox".to_owned(),
                 gas_prices: Some("0.000001ujunox".to_owned()),
+                ..Default::default()
             },
         }
     }
 
-    fn get_initialized_address(&self, tx: &TxQueryResponse) -> String {
-        
-        tx.logs
-            .first()
-            .unwrap()
-            .events
-            .get(0)
-            .unwrap()
-            .attributes
-            .get(0)
-            .unwrap()
-            .value
-            .clone()
+    fn get_stored_code_id(&self, tx: &TxQueryResponse) -> Result<String, WarpError> {
+        events::code_id(tx)
+    }
+
+    fn get_initialized_address(&self, tx: &TxQueryResponse) -> Result<String, WarpError> {
+        events::contract_address(tx)
     }
 
     fn init_frontend(&self, _dir: &PathBuf) -> Result<(), WarpError> {
@@ -1,19 +1,20 @@
 use std::{
-    fs::File, io::Write, path::PathBuf, process::{Command, Stdio}, time::Duration
+    fs::File, io::Write, path::PathBuf, process::{Command, Stdio}
 };
 
 use owo_colors::OwoColorize;
+use regex::Regex;
 use serde_json::Value;
 
 use crate::{
     cosmos::{
-        keys_show::KeysShowResponse, tx_query::TxQueryResponse,
+        code_info::{CodeInfoResponse, ContractInfoResponse}, events, keys_show::KeysShowResponse, proposal, tx_query::TxQueryResponse,
     }, commands::config::NetworkConfig, error::WarpError, utils::{file_util, project_config::Network}
 };
 
-use crate::utils::{command_util::CommandWithInput, project_config::ProjectConfig};
+use crate::utils::{command_util::CommandWithInput, confirmation, gas, offline_tx, project_config::{InstantiatePermission, ProjectConfig}, wasm_check::{ChainCapabilities, DEFAULT_MAX_WASM_SIZE}};
 
-use super::chain_profile::ChainProfile;
+use super::chain_profile::{admin_tx, ChainProfile, InstantiateOptions};
 
 pub struct SecretNetworkProfile;
 
@@ -29,6 +30,14 @@ impl ChainProfile for SecretNetworkProfile {
         "scrt".to_owned()
     }
 
+    fn address_prefix(&self) -> Option<String> {
+        Some("secret".to_owned())
+    }
+
+    fn coin_type(&self) -> u32 {
+        529
+    }
+
     fn get_common_cli_args<'a, 'b>(
         &self,
         tx: bool,
@@ -50,18 +59,19 @@ impl ChainProfile for SecretNetworkProfile {
                 "block".to_string(),
             ];
             args.append(&mut tx_args);
+            args.extend(config.keyring_cli_args());
+            if config.generate_only.is_some() {
+                args.push("--generate-only".to_string());
+            }
         }
-        if store {
-            let mut store_args = vec![
-                
-                "--gas".to_string(),
-                "auto".to_string(),
-                "--gas-adjustment".to_string(),
-                "2".to_string(),
-                "--gas-prices".to_string(),
-                config.network.gas_prices.clone().unwrap_or("0.0125uscrt".to_owned()),
-            ];
-            args.append(&mut store_args);
+        if store || (tx && config.network.gas.is_some()) {
+            args.extend(config.gas_cli_args(store));
+            args.push("--gas-prices".to_string());
+            args.push(match config.network.gas_prices.as_deref() {
+                Some(x) if x != "auto" => x.to_owned(),
+                // secretcli can't estimate the gas price
+                _ => "0.0125uscrt".to_owned(),
+            });
         }
         args
     }
@@ -75,6 +85,7 @@ impl ChainProfile for SecretNetworkProfile {
         let mut tx = Command::new(self.get_executable_name());
         tx.args(vec!["keys", "show", account_id])
             .args(self.get_common_cli_args(false, false, false, config))
+            .args(config.keyring_cli_args())
             .stdout(Stdio::piped())
             .stdin(if password.is_some() {
                 Stdio::piped()
@@ -98,9 +109,16 @@ impl ChainProfile for SecretNetworkProfile {
         &self,
         contract: &str,
         from: &str,
+        permission: Option<&InstantiatePermission>,
         password: Option<&str>,
         config: &ProjectConfig,
     ) -> Result<TxQueryResponse, WarpError> {
+        if permission.is_some() {
+            return Err(WarpError::UnsupportedFeature(
+                "instantiate_permission".to_owned(),
+                self.get_profile_name(),
+            ));
+        }
         let mut tx = Command::new(self.get_executable_name());
         tx.args(vec!["tx", "compute", "store", contract, "--from", from])
             .args(self.get_common_cli_args(true, true, true, config))
@@ -120,6 +138,9 @@ impl ChainProfile for SecretNetworkProfile {
             let bytes = out.stdout.clone();
             json_data = bytes;
         }
+        if let Some(path) = &config.generate_only {
+            return offline_tx::save_unsigned(path, &json_data);
+        }
         let response: TxQueryResponse = serde_json::from_slice(json_data.as_slice())?;
         if response.code != 0 {
             return Err(WarpError::TxFailed(response.txhash, response.raw_log));
@@ -131,14 +152,18 @@ impl ChainProfile for SecretNetworkProfile {
     fn instantiate_contract(
         &self,
         code_id: &str,
-        from: &str,
-        admin: &str,
-        label: &str,
         init_msg: &str,
-        coins: Option<String>,
+        from: &str,
+        options: &InstantiateOptions,
         password: Option<&str>,
         config: &ProjectConfig,
     ) -> Result<TxQueryResponse, WarpError> {
+        if options.salt.is_some() {
+            return Err(WarpError::UnsupportedFeature(
+                "instantiate2".to_owned(),
+                self.get_profile_name(),
+            ));
+        }
         let mut tx = Command::new(self.get_executable_name());
         tx.args(vec![
             "tx",
@@ -149,12 +174,14 @@ impl ChainProfile for SecretNetworkProfile {
             "--from",
             from,
             "--label",
-            label,
+            options.label,
             "--amount",
-            &coins.unwrap_or_default(),
-            "--admin",
-            admin,
+            &options.coins.clone().unwrap_or_default(),
         ])
+        .args(match options.admin {
+            Some(admin) => vec!["--admin", admin],
+            None => vec![],
+        })
         .args(self.get_common_cli_args(true, true, false, config))
         .stdout(Stdio::piped())
         .stdin(if password.is_some() {
@@ -173,6 +200,9 @@ impl ChainProfile for SecretNetworkProfile {
             json_data = bytes;
         }
         json_data.as_slice().iter().for_each(|x| print!("{}", *x as char));
+        if let Some(path) = &config.generate_only {
+            return offline_tx::save_unsigned(path, &json_data);
+        }
         let response: TxQueryResponse = serde_json::from_slice(json_data.as_slice())?;
         if response.code != 0 {
             let tx = Command::new(self.get_executable_name())
@@ -221,6 +251,9 @@ impl ChainProfile for SecretNetworkProfile {
             let bytes = out.stdout.clone();
             json_data = bytes;
         }
+        if let Some(path) = &config.generate_only {
+            return offline_tx::save_unsigned(path, &json_data);
+        }
         let response: TxQueryResponse = serde_json::from_slice(json_data.as_slice())?;
         if response.code != 0 {
             return Err(WarpError::TxFailed(response.txhash, response.raw_log));
@@ -265,6 +298,9 @@ impl ChainProfile for SecretNetworkProfile {
             let bytes = out.stdout.clone();
             json_data = bytes;
         }
+        if let Some(path) = &config.generate_only {
+            return offline_tx::save_unsigned(path, &json_data);
+        }
         let response: TxQueryResponse = serde_json::from_slice(json_data.as_slice())?;
         if response.code != 0 {
             return Err(WarpError::TxFailed(response.txhash, response.raw_log));
@@ -273,33 +309,57 @@ impl ChainProfile for SecretNetworkProfile {
         Ok(response)
     }
 
-    // TODO: Make this ugly thing go away once a better solution is confirmed to be working
+    fn update_admin(
+        &self,
+        contract_address: &str,
+        new_admin: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError> {
+        admin_tx(self, "compute", contract_address, Some(new_admin), from, password, config)
+    }
+
+    fn clear_admin(
+        &self,
+        contract_address: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError> {
+        admin_tx(self, "compute", contract_address, None, from, password, config)
+    }
+
     fn query_tx(
         &self,
         tx_hash: &str,
         config: &ProjectConfig,
     ) -> Result<TxQueryResponse, WarpError> {
-        let mut retries = 10;
-        loop {
-            let cmd = Command::new(self.get_executable_name())
-                .args(vec!["q", "tx", tx_hash])
-                .args(self.get_common_cli_args(false, true, false, config))
-                .stdin(Stdio::inherit())
-                .output()?;
-            let tx = cmd.stdout;
-            let stderr = cmd.stderr;
-            if !stderr.is_empty() && retries > 0 {
-                // crude but will do for beta
-                retries -= 1;
-                std::thread::sleep(Duration::from_millis(1958));
-                continue;
-            }
-            let response: TxQueryResponse = serde_json::from_slice(tx.as_slice())?;
-            if response.code != 0 {
-                return Err(WarpError::TxFailed(response.txhash, response.raw_log));
-            }
-            return Ok(response);
+        let response = confirmation::wait_for_tx(
+            tx_hash,
+            config,
+            || {
+                let cmd = Command::new(self.get_executable_name())
+                    .args(vec!["q", "tx", tx_hash])
+                    .args(self.get_common_cli_args(false, true, false, config))
+                    .stdin(Stdio::inherit())
+                    .output()?;
+                confirmation::parse_tx_lookup(&cmd)
+            },
+            || self.query_block_height(config),
+        )?;
+        if response.code != 0 {
+            return Err(WarpError::TxFailed(response.txhash, response.raw_log));
         }
+        Ok(response)
+    }
+
+    fn query_block_height(&self, config: &ProjectConfig) -> Result<u64, WarpError> {
+        let cmd = Command::new(self.get_executable_name())
+            .args(vec!["status", "--node", &config.network.rpc_url])
+            .stdin(Stdio::inherit())
+            .output()?;
+        confirmation::parse_block_height(&cmd)
     }
 
     fn query_contract_smart(
@@ -328,6 +388,190 @@ impl ChainProfile for SecretNetworkProfile {
         Ok(response)
     }
 
+    fn query_contract_info(
+        &self,
+        contract: &str,
+        config: &ProjectConfig,
+    ) -> Result<ContractInfoResponse, WarpError> {
+        let cmd = Command::new(self.get_executable_name())
+            .args(vec!["q", "compute", "contract-info", contract])
+            .args(self.get_common_cli_args(false, true, false, config))
+            .stdin(Stdio::inherit())
+            .output()?;
+        if !cmd.status.success() {
+            let msg = String::from_utf8(cmd.stderr)?;
+            return Err(WarpError::UnderlyingCliError(msg));
+        }
+        let response: ContractInfoResponse = serde_json::from_slice(cmd.stdout.as_slice())?;
+        Ok(response)
+    }
+
+    fn query_code_info(
+        &self,
+        code_id: &str,
+        config: &ProjectConfig,
+    ) -> Result<CodeInfoResponse, WarpError> {
+        // The compute module has no `code-info` query, so the code is looked up in the full list
+        let cmd = Command::new(self.get_executable_name())
+            .args(vec!["q", "compute", "list-code"])
+            .args(self.get_common_cli_args(false, true, false, config))
+            .stdin(Stdio::inherit())
+            .output()?;
+        if !cmd.status.success() {
+            let msg = String::from_utf8(cmd.stderr)?;
+            return Err(WarpError::UnderlyingCliError(msg));
+        }
+        let response: Value = serde_json::from_slice(cmd.stdout.as_slice())?;
+        let codes = response.get("code_infos").unwrap_or(&response);
+        let code = codes
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|x| match &x["code_id"] {
+                Value::String(id) => id == code_id,
+                id => id.as_u64().is_some_and(|id| code_id.parse() == Ok(id)),
+            })
+            .ok_or_else(|| WarpError::CodeIdNotFound(code_id.to_owned()))?;
+        Ok(CodeInfoResponse {
+            code_id: code_id.to_owned(),
+            creator: code["creator"].as_str().unwrap_or_default().to_owned(),
+            checksum: code["code_hash"].as_str().unwrap_or_default().to_owned(),
+            instantiate_permission: None,
+        })
+    }
+
+    fn query_contract_by_label(
+        &self,
+        label: &str,
+        config: &ProjectConfig,
+    ) -> Result<Option<String>, WarpError> {
+        let cmd = Command::new(self.get_executable_name())
+            .args(vec!["q", "compute", "label", label])
+            .args(self.get_common_cli_args(false, true, false, config))
+            .stdin(Stdio::inherit())
+            .output()?;
+        let stdout = String::from_utf8(cmd.stdout)?;
+        if !cmd.status.success() {
+            let msg = String::from_utf8(cmd.stderr)?;
+            if msg.to_lowercase().contains("not found") {
+                return Ok(None);
+            }
+            return Err(WarpError::UnderlyingCliError(msg));
+        }
+        let address = Regex::new(r"secret1[0-9a-z]{38,}")?
+            .find(&stdout)
+            .map(|x| x.as_str().to_owned());
+        Ok(address)
+    }
+
+    fn find_code_by_checksum(
+        &self,
+        checksum: &str,
+        config: &ProjectConfig,
+    ) -> Result<Option<String>, WarpError> {
+        let cmd = Command::new(self.get_executable_name())
+            .args(vec!["q", "compute", "list-code"])
+            .args(self.get_common_cli_args(false, true, false, config))
+            .stdin(Stdio::inherit())
+            .output()?;
+        if !cmd.status.success() {
+            let msg = String::from_utf8(cmd.stderr)?;
+            return Err(WarpError::UnderlyingCliError(msg));
+        }
+        let response: Value = serde_json::from_slice(cmd.stdout.as_slice())?;
+        let codes = response.get("code_infos").unwrap_or(&response);
+        Ok(codes
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|x| {
+                x["code_hash"]
+                    .as_str()
+                    .is_some_and(|x| x.eq_ignore_ascii_case(checksum))
+            })
+            .filter_map(|x| match &x["code_id"] {
+                Value::String(id) => id.parse::<u64>().ok(),
+                id => id.as_u64(),
+            })
+            .max()
+            .map(|x| x.to_string()))
+    }
+
+    fn store_code_msg_type(&self) -> Result<String, WarpError> {
+        // The compute module's `MsgStoreCode` can't be executed by the gov module
+        Err(WarpError::UnsupportedFeature(
+            "upload_via = 'proposal'".to_owned(),
+            self.get_profile_name(),
+        ))
+    }
+
+    fn query_proposal_status(
+        &self,
+        proposal_id: &str,
+        config: &ProjectConfig,
+    ) -> Result<String, WarpError> {
+        let cmd = Command::new(self.get_executable_name())
+            .args(vec!["q", "gov", "proposal", proposal_id])
+            .args(self.get_common_cli_args(false, true, false, config))
+            .stdin(Stdio::inherit())
+            .output()?;
+        if !cmd.status.success() {
+            let msg = String::from_utf8(cmd.stderr)?;
+            return Err(WarpError::UnderlyingCliError(msg));
+        }
+        let response: Value = serde_json::from_slice(cmd.stdout.as_slice())?;
+        proposal::proposal_status(&response)
+            .ok_or_else(|| WarpError::UnderlyingCliError(String::from_utf8_lossy(&cmd.stdout).to_string()))
+    }
+
+    fn query_gas_price(&self, _config: &ProjectConfig) -> Result<Option<String>, WarpError> {
+        // Secret does not expose its minimum gas prices, the configured price is used
+        Ok(None)
+    }
+
+    fn simulate_execute(
+        &self,
+        contract_address: &str,
+        msg: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<u64, WarpError> {
+        let mut tx = Command::new(self.get_executable_name());
+        tx.args(vec![
+            "tx",
+            "compute",
+            "execute",
+            contract_address,
+            msg,
+            "--from",
+            from,
+            "--dry-run",
+            "--gas",
+            "auto",
+        ])
+        .args(self.get_common_cli_args(false, true, false, config))
+        .args(vec!["--chain-id", &config.network.chain_id])
+        .args(config.keyring_cli_args())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .stdin(if password.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::inherit()
+        });
+        let out = match password {
+            Some(pass) => tx.call_process_with_input(pass)?,
+            None => tx.output()?,
+        };
+        gas::parse_gas_estimate(&out)
+    }
+
+    fn chain_capabilities(&self) -> ChainCapabilities {
+        // Secret Network runs its own fork of the VM with encrypted state and randomness
+        ChainCapabilities::new(&["iterator", "staking", "stargate", "random", "secret"], "cosmwasm_1_1", DEFAULT_MAX_WASM_SIZE)
+    }
+
     fn init_project(&self, dir: &PathBuf) -> Result<(), WarpError> {
         println!("Initializing new workspace...");
         let cmd = Command::new("git")
@@ -416,6 +660,7 @@ impl ChainProfile for SecretNetworkProfile {
                 rpc_url: "https://secretnetwork-rpc.lavenderfive.com:443".to_owned(),
                 denom: "uscrt".to_owned(),
                 gas_prices: Some("0.0125uscrt".to_owned()),
+                ..Default::default()
             },
             NetworkConfig::Testnet => Network {
                 profile: self.get_profile_name(),
@@ -423,6 +668,7 @@ impl ChainProfile for SecretNetworkProfile {
                 rpc_url: "https://rpc.pulsar-3.secretsaturn.net".to_owned(),
                 denom: "uscrt".to_owned(),
                 gas_prices: Some("0.0125uscrt".to_owned()),
+                ..Default::default()
             },
             NetworkConfig::Local => Network {
                 profile: self.get_profile_name(),
@@ -430,12 +676,18 @@ impl ChainProfile for SecretNetworkProfile {
                 rpc_url: "http://localhost:26657".to_owned(), // TODO: Add local node URL
                 denom: "uscrtx".to_owned(),
                 gas_prices: Some("0.0125uscrt".to_owned()),
+                ..Default::default()
             },
         }
     }
 
-    fn get_initialized_address(&self, tx: &TxQueryResponse) -> String {
-        tx.logs.first().unwrap().events.get(1).unwrap().attributes.last().unwrap().value.clone()
+    fn get_stored_code_id(&self, tx: &TxQueryResponse) -> Result<String, WarpError> {
+        // The compute module reports everything in `message` events
+        events::attribute(tx, "message", "code_id")
+    }
+
+    fn get_initialized_address(&self, tx: &TxQueryResponse) -> Result<String, WarpError> {
+        events::attribute(tx, "message", "contract_address")
     }
 
     fn init_frontend(&self, dir: &PathBuf) -> Result<(), WarpError> {
@@ -1,23 +1,81 @@
-use std::{path::PathBuf, time::Duration};
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 
 use crate::{
-    chains::chain_profile::ChainProfile,
-    commands::BuildCommand,
+    chains::chain_profile::{ChainProfile, InstantiateOptions},
+    commands::{check, BuildCommand},
+    cosmos::{
+        address::{instantiate2_address, module_address},
+        proposal::{store_code_proposal, StoreCodeUpload},
+    },
     error::WarpError,
     executable::Executable,
     utils::{
-        deployment_result::DeploymentResult, deployment_task::DeploymentTask,
-        project_config::ProjectConfig,
+        build_manifest::BuildManifest,
+        cost_report::{CostKind, CostReport},
+        credentials, deployment_export,
+        deployment_result::DeploymentResult,
+        deployment_task::DeploymentTask,
+        file_util,
+        gas::retry_out_of_gas,
+        git::GitInfo,
+        offline_tx::{self, PendingTx, PendingTxs, TxKind},
+        project_config::{
+            AutoDeployStep, ExportFormat, InstantiatePermission, ProjectConfig, UploadVia,
+        },
+        workspace,
     },
 };
-use clap::Args;
+use clap::{Args, Subcommand};
 use owo_colors::OwoColorize;
 
 #[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct AutoDeployCommand {
+    #[command(subcommand)]
+    pub command: Option<DeploySubcommand>,
     #[arg(short, long)]
     /// The name of the new contract
     pub rebuild: bool,
+    /// Print the deployment plan without executing it
+    #[arg(long)]
+    pub plan: bool,
+    /// Don't ask for a keyring password (see also WARP_KEYRING_PASSWORD)
+    #[arg(long)]
+    pub no_password: bool,
+    /// Write unsigned transactions to .warp/txs/ instead of broadcasting them
+    #[arg(long)]
+    pub generate_only: bool,
+    /// Don't check the artifacts before uploading them (see `warp check`)
+    #[arg(long)]
+    pub skip_check: bool,
+    /// Reuse uploaded code with the same checksum and don't migrate contracts already running it
+    #[arg(long)]
+    pub skip_unchanged: bool,
+    /// Continue once the code upload proposal with this ID has passed (with `upload_via = "proposal"`)
+    #[arg(long, value_name = "ID")]
+    pub await_proposal: Option<String>,
+    /// Seconds to wait for the proposal with `--await-proposal` before giving up
+    #[arg(long, value_name = "SECONDS", default_value_t = 3600)]
+    pub proposal_timeout: u64,
+}
+
+#[derive(Subcommand)]
+pub enum DeploySubcommand {
+    /// Export the deployments for frontends and tests
+    Export(DeployExportArgs),
+}
+
+#[derive(Args, Clone)]
+pub struct DeployExportArgs {
+    /// Output format
+    #[arg(long, short, value_enum, default_value = "ts")]
+    pub format: ExportFormat,
+    /// Output file (prints to stdout if omitted)
+    #[arg(long, short)]
+    pub out: Option<PathBuf>,
 }
 
 impl Executable for AutoDeployCommand {
@@ -33,6 +91,23 @@ impl Executable for AutoDeployCommand {
         let project_root = project_root.unwrap();
         let config = config.unwrap();
 
+        if let Some(DeploySubcommand::Export(x)) = &self.command {
+            let deployments = if DeploymentResult::exists()? {
+                DeploymentResult::parse()?.1
+            } else {
+                DeploymentResult::default()
+            };
+            let output = deployment_export::render(x.format, &config, &deployments)?;
+            match &x.out {
+                Some(path) => {
+                    std::fs::write(path, output)?;
+                    println!("{} {}", "Exported:".bright_green(), path.display());
+                }
+                None => print!("{output}"),
+            }
+            return Ok(());
+        }
+
         if config.autodeploy.account_id.is_empty() {
             println!(
                 "{} {}",
@@ -43,20 +118,43 @@ impl Executable for AutoDeployCommand {
             return Ok(());
         }
 
-        let password =
-            rpassword::prompt_password("Enter your keyring password (if using/needed):")?;
-        let password = if password.is_empty() {
-            None
-        } else {
-            Some(password.as_str())
-        };
+        if self.plan {
+            return Self::print_plan(&project_root, &config, profile.as_ref());
+        }
+
+        let password = credentials::keyring_password(self.no_password, &project_root, &config)?;
+        let password = password.as_deref();
 
         if self.rebuild {
-            BuildCommand { optimized: true }.execute(
-                Some(project_root),
-                Some(config.clone()),
-                profile,
-            )?;
+            // Only the contracts the steps deploy are built
+            let mut packages: Vec<String> = vec![];
+            for step in config.autodeploy.steps.iter() {
+                match workspace::find_contract_for_artifact(&project_root, &step.contract)? {
+                    Some(x) if !packages.contains(&x.name) => packages.push(x.name),
+                    _ => (),
+                }
+            }
+            if packages.is_empty() {
+                println!("No workspace contracts to build.");
+            } else {
+                BuildCommand {
+                    optimized: true,
+                    packages,
+                    ..Default::default()
+                }
+                .execute(Some(project_root.clone()), Some(config.clone()), profile)?;
+            }
+        }
+
+        if !self.skip_check {
+            let mut artifacts: Vec<PathBuf> = vec![];
+            for step in config.autodeploy.steps.iter() {
+                let artifact = PathBuf::from(&step.contract);
+                if !artifacts.contains(&artifact) {
+                    artifacts.push(artifact);
+                }
+            }
+            check::check_artifacts(&project_root, &artifacts, &config, profile.as_ref())?;
         }
 
         let deployment_account = profile
@@ -65,35 +163,108 @@ impl Executable for AutoDeployCommand {
 
         println!("Deploying from: {}", &deployment_account);
 
+        let deploy_existed = DeploymentResult::exists()?;
+        let mut deployment_file = if deploy_existed {
+            DeploymentResult::parse()?.1
+        } else {
+            DeploymentResult::default()
+        };
+        let deployment_key = config.deployment_key();
+        let mut pending = PendingTxs::load(&project_root)?;
+        let manifest = BuildManifest::load(&project_root)?;
+
         println!("Uploading contracts to the chain...");
+        let via_proposal = config.network.upload_via == Some(UploadVia::Proposal);
+        // Checked before anything is uploaded, as not every profile can upload via proposals
+        let store_code_msg_type = match via_proposal {
+            true => profile.store_code_msg_type()?,
+            false => String::new(),
+        };
+        // Index in `store_txs`, checksum and permission of the contracts uploaded by a proposal
+        let mut proposal_uploads: Vec<(usize, String, Option<InstantiatePermission>)> = vec![];
+        let mut costs = CostReport::default();
         let mut store_txs: Vec<DeploymentTask> = vec![];
         for step in config.autodeploy.steps.iter() {
             print!(" {} {}", "=>".bright_yellow(), step.contract.bright_blue());
-            let response = profile.store_contract(
-                &step.contract,
-                &config.autodeploy.account_id,
-                password,
-                &config,
-            )?;
+            let checksum = hex::encode(file_util::sha256_file(project_root.join(&step.contract))?);
+            let record = deployment_file.record(&deployment_key, &step.id);
+            if self.generate_only || via_proposal || self.skip_unchanged {
+                // Code uploaded by an earlier (imported or proposed) transaction can be used right away
+                if let (Some(code_id), true) = (&record.code_id, record.checksum.as_ref() == Some(&checksum)) {
+                    println!("\t{} - CODE: {}", "Already uploaded.".bright_green(), code_id.bright_green());
+                    store_txs.push(DeploymentTask {
+                        step,
+                        code_id: Some(code_id.clone()),
+                        contract_address: None,
+                    });
+                    continue;
+                }
+            }
+            let permission = step.instantiate_permission.as_ref().map(|x| match x {
+                InstantiatePermission::AnyOf(addresses) => InstantiatePermission::AnyOf(
+                    addresses
+                        .iter()
+                        .map(|x| x.replace("$account_id", &deployment_account))
+                        .collect(),
+                ),
+                x => x.clone(),
+            });
+            if via_proposal {
+                println!("\t{}", "Uploading via proposal.".bright_yellow());
+                proposal_uploads.push((store_txs.len(), checksum, permission));
+                store_txs.push(DeploymentTask {
+                    step,
+                    code_id: None,
+                    contract_address: None,
+                });
+                continue;
+            }
+            if self.generate_only {
+                let path = pending.add(
+                    &project_root,
+                    PendingTx {
+                        deployment: deployment_key.clone(),
+                        step: step.id.clone(),
+                        kind: TxKind::Store,
+                        checksum: Some(checksum),
+                    },
+                )?;
+                profile.store_contract(
+                    &step.contract,
+                    &config.autodeploy.account_id,
+                    permission.as_ref(),
+                    password,
+                    &config.for_step(step).with_generate_only(path.clone()),
+                )?;
+                println!("\t{} {}", "Generated:".bright_green(), path.display());
+                store_txs.push(DeploymentTask {
+                    step,
+                    code_id: None,
+                    contract_address: None,
+                });
+                continue;
+            }
+            let response = retry_out_of_gas(&config.for_step(step), |config| {
+                profile.store_contract(
+                    &step.contract,
+                    &config.autodeploy.account_id,
+                    permission.as_ref(),
+                    password,
+                    config,
+                )
+            })?;
             // let full_tx = profile.query_tx(&response.txhash)?;
-            let code_id = response
-                .logs
-                .last()
-                .unwrap()
-                .events
-                .last()
-                .unwrap()
-                .attributes
-                .last()
-                .unwrap()
-                .value
-                .clone();
+            let code_id = profile.get_stored_code_id(&response)?;
             println!(
                 "\t{} ({}) - CODE: {}",
                 "Done.".bright_green(),
                 &response.txhash.bright_blue(),
                 code_id.bright_green()
             );
+            record.code_id = Some(code_id.clone());
+            record.build = manifest.find(&step.contract, &checksum).cloned();
+            record.checksum = Some(checksum);
+            costs.add(&step.id, CostKind::Store, &response);
 
             store_txs.push(DeploymentTask {
                 step: &step,
@@ -101,19 +272,69 @@ impl Executable for AutoDeployCommand {
                 contract_address: None,
             });
         }
-        // Only add the extra wait if deploying one contract since otherwise it'll be fine anyway
-        if store_txs.len() == 1 {
-            std::thread::sleep(Duration::from_millis(4500));
+        if !proposal_uploads.is_empty() {
+            let proposal_id = match &self.await_proposal {
+                Some(x) => x,
+                None => {
+                    let path = Self::write_store_proposal(
+                        &project_root,
+                        &config,
+                        &store_code_msg_type,
+                        &deployment_account,
+                        &store_txs,
+                        &proposal_uploads,
+                    )?;
+                    println!(
+                        "{} The code upload proposal was written to '{}'. Submit it with `{} tx gov submit-proposal <FILE> --from {}`, then run `warp deploy --await-proposal <ID>` to continue once it passes.",
+                        "Done.".bright_green(),
+                        path.display(),
+                        profile.get_executable_name(),
+                        config.autodeploy.account_id
+                    );
+                    return Ok(());
+                }
+            };
+            Self::await_proposal(
+                proposal_id,
+                Duration::from_secs(self.proposal_timeout),
+                &config,
+                profile.as_ref(),
+            )?;
+            for (index, checksum, _) in proposal_uploads.iter() {
+                let task = &mut store_txs[*index];
+                let code_id = profile
+                    .find_code_by_checksum(checksum, &config)?
+                    .ok_or_else(|| WarpError::CodeNotFound(checksum.clone()))?;
+                println!(
+                    " {} {} - CODE: {}",
+                    "=>".bright_yellow(),
+                    task.step.contract.bright_blue(),
+                    code_id.bright_green()
+                );
+                let record = deployment_file.record(&deployment_key, &task.step.id);
+                record.code_id = Some(code_id.clone());
+                record.checksum = Some(checksum.clone());
+                record.build = manifest.find(&task.step.contract, checksum).cloned();
+                task.code_id = Some(code_id);
+            }
         }
         println!("Instantiating uploaded contracts...");
 
-        let deploy_existed = DeploymentResult::exists()?;
-        let mut deployment_file = if deploy_existed {
-            DeploymentResult::parse()?.1
-        } else {
-            DeploymentResult::default()
-        };
-        let current_network = deployment_file.network(&config.network.chain_id);
+        let current_network = deployment_file.network(&deployment_key);
+
+        // Addresses of contracts instantiated with a salt are known up front, so any step can reference them
+        for t in store_txs.iter_mut() {
+            if let (Some(salt), false) = (&t.step.salt, t.step.store_only) {
+                if !current_network.contains_key(&t.step.id) {
+                    let checksum = file_util::sha256_file(project_root.join(&t.step.contract))?;
+                    t.contract_address = Some(instantiate2_address(
+                        &checksum,
+                        &deployment_account,
+                        salt.as_bytes(),
+                    )?);
+                }
+            }
+        }
 
         for task in config.autodeploy.steps.iter() {
             if task.store_only {
@@ -128,39 +349,93 @@ impl Executable for AutoDeployCommand {
             let contract_addr: String;
             if !current_network.contains_key(&task.id) {
                 print!(" {} {}", "=>".bright_yellow(), &task.contract.bright_blue());
+                if self.generate_only {
+                    // Transactions can only be generated once everything they reference exists
+                    let waiting_for = Self::unresolved_references(task, &store_txs);
+                    if !waiting_for.is_empty() {
+                        println!(
+                            "\t{} {}",
+                            "Waiting for:".bright_yellow(),
+                            waiting_for.join(", ")
+                        );
+                        continue;
+                    }
+                }
                 let init_msg =
-                    Self::format_init_message(&task.init_msg, &store_txs, &deployment_account);
-                let label = if config.autodeploy.make_labels_unique {
-                    let mut l = task.label.clone();
-                    l.push('-');
-                    l.push_str(
-                        &std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs()
-                            .to_string(),
-                    );
-                    l
-                } else {
-                    task.label.clone()
+                    Self::format_init_message(&task.init_msg, &store_txs, &deployment_account)?;
+                let label = Self::format_label(&config, task, &project_root)?;
+                if let Some(address) = profile.query_contract_by_label(&label, &config)? {
+                    return Err(WarpError::LabelTaken(label, address));
+                }
+                let admin = match task.admin.as_deref() {
+                    None | Some("deployer") => Some(deployment_account.clone()),
+                    Some("none") => None,
+                    Some(x) => {
+                        let admin = Self::format_init_message(x, &store_txs, &deployment_account)?;
+                        // A reference that didn't resolve would be set as the admin verbatim
+                        if admin.is_empty() || admin.contains('$') || admin.contains('#') {
+                            return Err(WarpError::ContractIdNotFound(x.to_owned()));
+                        }
+                        Some(admin)
+                    }
                 };
+                let options = InstantiateOptions {
+                    label: &label,
+                    admin: admin.as_deref(),
+                    coins: task.coins.clone(),
+                    salt: task.salt.as_deref(),
+                };
+                if self.generate_only {
+                    let code_id = store_txs
+                        .iter()
+                        .find(|x| x.step.id == task.id)
+                        .and_then(|x| x.code_id.clone());
+                    let path = pending.add(
+                        &project_root,
+                        PendingTx {
+                            deployment: deployment_key.clone(),
+                            step: task.id.clone(),
+                            kind: TxKind::Instantiate,
+                            checksum: None,
+                        },
+                    )?;
+                    profile.instantiate_contract(
+                        code_id.as_ref().unwrap(),
+                        &init_msg,
+                        &config.autodeploy.account_id,
+                        &options,
+                        password,
+                        &config.for_step(task).with_generate_only(path.clone()),
+                    )?;
+                    println!("\t{} {}", "Generated:".bright_green(), path.display());
+                    continue;
+                }
                 let t = store_txs.iter_mut().find(|x| &x.step.id == &task.id);
                 if t.is_none() {
                     break;
                 }
                 let t = t.unwrap();
-                let init_tx = profile.instantiate_contract(
-                    t.code_id.as_ref().unwrap(),
-                    &config.autodeploy.account_id,
-                    &deployment_account,
-                    &label,
-                    &init_msg,
-                    task.coins.clone(),
-                    password,
-                    &config,
-                )?;
+                let init_tx = retry_out_of_gas(&config.for_step(task), |config| {
+                    profile.instantiate_contract(
+                        t.code_id.as_ref().unwrap(),
+                        &init_msg,
+                        &config.autodeploy.account_id,
+                        &options,
+                        password,
+                        config,
+                    )
+                })?;
                 // let init_full_tx = profile.query_tx(&init_tx)?;
-                let addr = profile.get_initialized_address(&init_tx);
+                costs.add(&task.id, CostKind::Instantiate, &init_tx);
+                let addr = profile.get_initialized_address(&init_tx)?;
+                if let Some(predicted) = t.contract_address.as_ref().filter(|x| *x != &addr) {
+                    println!(
+                        "\n{} {} {}",
+                        "Warning!".bright_yellow(),
+                        "The contract address doesn't match the predicted one:".yellow(),
+                        predicted.bright_yellow()
+                    );
+                }
                 t.contract_address = Some(addr.clone());
                 contract_addr = addr.clone();
                 println!(
@@ -179,15 +454,62 @@ impl Executable for AutoDeployCommand {
                 let t = t.unwrap();
                 contract_addr = current_network.get(&task.id).unwrap().clone();
                 t.contract_address = Some(contract_addr.clone());
-                let _tx = profile.migrate_contract(
-                    &contract_addr,
-                    &t.code_id.as_ref().unwrap(),
-                    &config.autodeploy.account_id,
-                    &task.migrate_msg.as_ref().unwrap_or(&String::from("{}")),
-                    password,
-                    &config,
-                )?;
+                if self.generate_only {
+                    let code_id = match &t.code_id {
+                        Some(x) => x.clone(),
+                        None => {
+                            println!("\t{} the code upload", "Waiting for:".bright_yellow());
+                            continue;
+                        }
+                    };
+                    let contract = profile.query_contract_info(&contract_addr, &config)?;
+                    if contract.contract_info.code_id == code_id {
+                        println!("\t{} (CODE ID: {})", "Up to date.".bright_green(), code_id.bright_cyan());
+                        continue;
+                    }
+                    let path = pending.add(
+                        &project_root,
+                        PendingTx {
+                            deployment: deployment_key.clone(),
+                            step: task.id.clone(),
+                            kind: TxKind::Migrate,
+                            checksum: None,
+                        },
+                    )?;
+                    profile.migrate_contract(
+                        &contract_addr,
+                        &code_id,
+                        &config.autodeploy.account_id,
+                        task.migrate_msg.as_deref().unwrap_or("{}"),
+                        password,
+                        &config.for_step(task).with_generate_only(path.clone()),
+                    )?;
+                    println!("\t{} {}", "Generated:".bright_green(), path.display());
+                    continue;
+                }
+                if self.skip_unchanged {
+                    let contract = profile.query_contract_info(&contract_addr, &config)?;
+                    if t.code_id.as_ref() == Some(&contract.contract_info.code_id) {
+                        println!(
+                            "\t{} (CODE ID: {})",
+                            "Up to date.".bright_green(),
+                            contract.contract_info.code_id.bright_cyan()
+                        );
+                        continue;
+                    }
+                }
+                let tx = retry_out_of_gas(&config.for_step(task), |config| {
+                    profile.migrate_contract(
+                        &contract_addr,
+                        t.code_id.as_ref().unwrap(),
+                        &config.autodeploy.account_id,
+                        task.migrate_msg.as_deref().unwrap_or("{}"),
+                        password,
+                        config,
+                    )
+                })?;
                 // let _full_tx = profile.query_tx(&tx.txhash)?;
+                costs.add(&task.id, CostKind::Migrate, &tx);
                 println!(
                     "\t{} (CODE ID: {} => {}) -- '{}'",
                     "Done.".bright_green(),
@@ -204,26 +526,274 @@ impl Executable for AutoDeployCommand {
                 .entry(task.id.clone())
                 .or_insert(contract_addr);
         }
+        if self.generate_only {
+            pending.save(&project_root)?;
+            println!(
+                "{} Unsigned transactions were written to '{}'. Sign them with `warp tx sign` and run `warp tx broadcast` for each, then run `warp deploy --generate-only` again for the remaining steps.",
+                "Done.".bright_green(),
+                offline_tx::TXS_DIR
+            );
+            return Ok(());
+        }
+        if !costs.is_empty() {
+            costs.print();
+            costs.save(
+                &mut deployment_file,
+                &deployment_key,
+                GitInfo::load(&project_root).map(|x| x.sha),
+            );
+        }
+        deployment_file
+            .networks
+            .insert(deployment_key.clone(), (&config.network).into());
         deployment_file.save()?;
+        for export in config.autodeploy.exports.iter() {
+            let path = project_root.join(&export.out);
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(
+                &path,
+                deployment_export::render(export.format, &config, &deployment_file)?,
+            )?;
+            println!(" {} {}", "Exported:".bright_green(), export.out.bright_blue());
+        }
         Ok(())
     }
 }
 
 impl AutoDeployCommand {
+    fn write_store_proposal(
+        project_root: &Path,
+        config: &ProjectConfig,
+        msg_type: &str,
+        deployment_account: &str,
+        store_txs: &[DeploymentTask],
+        uploads: &[(usize, String, Option<InstantiatePermission>)],
+    ) -> Result<PathBuf, WarpError> {
+        let mut contracts = vec![];
+        let mut summary = "Uploads the following contracts:\n".to_owned();
+        for (index, checksum, permission) in uploads.iter() {
+            let step = store_txs[*index].step;
+            contracts.push(StoreCodeUpload {
+                wasm: std::fs::read(project_root.join(&step.contract))?,
+                instantiate_permission: permission.clone(),
+            });
+            summary.push_str(&format!("- {} ({}), sha256: {}\n", step.label, step.contract, checksum));
+        }
+        let title = format!(
+            "Upload {}",
+            uploads
+                .iter()
+                .map(|(index, _, _)| store_txs[*index].step.label.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let proposal = store_code_proposal(
+            msg_type,
+            &module_address("gov", deployment_account)?,
+            &contracts,
+            &title,
+            &summary,
+            config.network.proposal_deposit.as_deref().unwrap_or_default(),
+        );
+        let dir = project_root.join(".warp/proposals");
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}-store-code.json", config.deployment_key()));
+        std::fs::write(&path, serde_json::to_string_pretty(&proposal)?)?;
+        Ok(path)
+    }
+
+    fn await_proposal(
+        proposal_id: &str,
+        timeout: Duration,
+        config: &ProjectConfig,
+        profile: &dyn ChainProfile,
+    ) -> Result<(), WarpError> {
+        let deadline = Instant::now() + timeout;
+        let mut last_status = String::new();
+        loop {
+            let status = profile.query_proposal_status(proposal_id, config)?;
+            match status.as_str() {
+                "PROPOSAL_STATUS_PASSED" => {
+                    println!("Proposal {} has passed.", proposal_id.bright_blue());
+                    return Ok(());
+                }
+                "PROPOSAL_STATUS_REJECTED" | "PROPOSAL_STATUS_FAILED" => {
+                    return Err(WarpError::ProposalNotPassed(proposal_id.to_owned(), status));
+                }
+                "PROPOSAL_STATUS_DEPOSIT_PERIOD" | "PROPOSAL_STATUS_VOTING_PERIOD" => {
+                    if status != last_status {
+                        println!(
+                            "Waiting for proposal {}: {}",
+                            proposal_id.bright_blue(),
+                            status.bright_yellow()
+                        );
+                        last_status = status;
+                    }
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        return Err(WarpError::ProposalTimeout(
+                            proposal_id.to_owned(),
+                            timeout.as_secs(),
+                        ));
+                    }
+                    std::thread::sleep(Duration::from_secs(15).min(remaining));
+                }
+                _ => {
+                    return Err(WarpError::UnknownProposalStatus(
+                        proposal_id.to_owned(),
+                        status,
+                    ))
+                }
+            }
+        }
+    }
+
+    fn print_plan(
+        project_root: &Path,
+        config: &ProjectConfig,
+        profile: &dyn ChainProfile,
+    ) -> Result<(), WarpError> {
+        let deployments = if DeploymentResult::exists()? {
+            DeploymentResult::parse()?.1
+        } else {
+            DeploymentResult::default()
+        };
+        let deployed = deployments.deployment.get(&config.deployment_key());
+
+        println!(
+            "Deployment plan for {} ({}):",
+            config.deployment_key().bright_blue(),
+            config.network.chain_id.bright_blue()
+        );
+        if config.network.upload_via == Some(UploadVia::Proposal) {
+            println!("Code is uploaded via a governance proposal.");
+        }
+        for step in config.autodeploy.steps.iter() {
+            let address = deployed.and_then(|x| x.get(&step.id));
+            let action = match (step.store_only, address) {
+                (true, _) => "store only",
+                (false, None) if step.salt.is_some() => "store + instantiate2",
+                (false, None) => "store + instantiate",
+                (false, Some(_)) => "store + migrate",
+            };
+            println!(
+                " {} {} ({}) - {}",
+                "=>".bright_yellow(),
+                step.id.bright_blue(),
+                step.contract,
+                action.bright_green()
+            );
+            if !step.store_only && address.is_none() {
+                println!(
+                    "     Label: '{}', admin: {}",
+                    Self::format_label(config, step, project_root)?,
+                    step.admin.as_deref().unwrap_or("deployer")
+                );
+            }
+            println!(
+                "     Upload access: {}",
+                step.instantiate_permission
+                    .as_ref()
+                    .map_or("chain default".to_owned(), |x| x.to_string())
+            );
+            if let Some(address) = address {
+                let contract = profile.query_contract_info(address, config)?;
+                let code = profile.query_code_info(&contract.contract_info.code_id, config)?;
+                println!(
+                    "     Deployed: {} (code {}, access: {})",
+                    address.bright_cyan(),
+                    code.code_id.bright_cyan(),
+                    code.instantiate_permission
+                        .map_or("n/a".to_owned(), |x| x.to_string())
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn format_label(
+        config: &ProjectConfig,
+        step: &AutoDeployStep,
+        project_root: &Path,
+    ) -> Result<String, WarpError> {
+        let template = match &config.autodeploy.label_template {
+            Some(x) => x.clone(),
+            None if config.autodeploy.make_labels_unique => "{label}-{timestamp}".to_owned(),
+            None => return Ok(step.label.clone()),
+        };
+        let mut label = template
+            .replace("{label}", &step.label)
+            .replace("{env}", &config.deployment_key())
+            .replace("{chain_id}", &config.network.chain_id)
+            .replace(
+                "{timestamp}",
+                &std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs()
+                    .to_string(),
+            );
+        if label.contains("{crate_version}") {
+            let version = workspace::find_contract_for_artifact(project_root, &step.contract)?
+                .map(|x| x.version)
+                .unwrap_or_default();
+            label = label.replace("{crate_version}", &version);
+        }
+        if label.contains("{git_") {
+            let git = GitInfo::load(project_root).ok_or(WarpError::GitUnavailable)?;
+            label = label
+                .replace("{git_sha}", &git.sha)
+                .replace("{git_short_sha}", &git.short_sha)
+                .replace("{git_branch}", &git.branch);
+        }
+        Ok(label)
+    }
+
+    /// Code uploads and contracts the instantiation of `step` depends on which don't exist yet
+    fn unresolved_references(step: &AutoDeployStep, tasks: &[DeploymentTask]) -> Vec<String> {
+        let mut missing = vec![];
+        if tasks.iter().any(|x| x.step.id == step.id && x.code_id.is_none()) {
+            missing.push(format!("code of {}", step.id));
+        }
+        let msg = format!("{} {}", step.init_msg, step.admin.as_deref().unwrap_or_default());
+        for task in tasks.iter() {
+            if msg.contains(&format!("${}", task.step.id)) && task.contract_address.is_none() {
+                missing.push(task.step.id.clone());
+            }
+            if msg.contains(&format!("#{}", task.step.id)) && task.code_id.is_none() {
+                missing.push(format!("code of {}", task.step.id));
+            }
+        }
+        missing.sort();
+        missing.dedup();
+        missing
+    }
+
     fn format_init_message(
         init_msg: &str,
         tasks: &[DeploymentTask],
         deployment_account: &str,
-    ) -> String {
-        let mut new_msg = init_msg.replace("$account_id", &deployment_account);
-        tasks.iter().for_each(|x| {
-            new_msg = new_msg
-                .replace(
-                    &format!("${}", &x.step.id),
-                    &x.contract_address.as_ref().unwrap_or(&String::new()),
-                )
-                .replace(&format!("#{}", &x.step.id), &x.code_id.as_ref().unwrap())
-        });
-        new_msg
+    ) -> Result<String, WarpError> {
+        let mut new_msg = init_msg.replace("$account_id", deployment_account);
+        // Longest IDs first, so that `$a` doesn't match the start of `$ab`
+        let mut tasks = tasks.iter().collect::<Vec<_>>();
+        tasks.sort_by_key(|x| std::cmp::Reverse(x.step.id.len()));
+        for x in tasks {
+            for (reference, value) in [
+                (format!("${}", x.step.id), &x.contract_address),
+                (format!("#{}", x.step.id), &x.code_id),
+            ] {
+                if !new_msg.contains(&reference) {
+                    continue;
+                }
+                let value = value
+                    .as_ref()
+                    .ok_or_else(|| WarpError::ContractIdNotFound(reference.clone()))?;
+                new_msg = new_msg.replace(&reference, value);
+            }
+        }
+        Ok(new_msg)
     }
 }
@@ -1,17 +1,43 @@
-use std::{path::PathBuf, process::Command};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
 pub use clap::{arg, Args};
+use owo_colors::OwoColorize;
 
 use crate::{
-    chains::chain_profile::ChainProfile, error::WarpError, executable::Executable,
-    utils::project_config::ProjectConfig,
+    chains::chain_profile::ChainProfile,
+    error::WarpError,
+    executable::Executable,
+    utils::{
+        build_manifest::BuildManifest,
+        git, optimizer,
+        project_config::ProjectConfig,
+        wasm_size,
+        watcher::{self, WorkspaceWatcher},
+        workspace::{self, ContractCrate},
+    },
 };
 
-#[derive(Args)]
+#[derive(Args, Default)]
 pub struct BuildCommand {
-    /// Build for production with 'workspace-optimizer' docker image
+    /// Build for production with the optimizer of `tooling.optimizer_backend`
     #[arg(default_value_t = false, short, long)]
     pub optimized: bool,
+    /// Only build these contracts (package or artifact names)
+    #[arg(short = 'p', long = "package", value_name = "CONTRACT")]
+    pub packages: Vec<String>,
+    /// Only build the contracts changed since their last build, or since a git ref (`--changed main`)
+    #[arg(long, num_args = 0..=1, default_missing_value = "", value_name = "GIT_REF")]
+    pub changed: Option<String>,
+    /// Run the optimizer even if the inputs haven't changed since the last optimized build
+    #[arg(long)]
+    pub force: bool,
+    /// Rebuild the affected contracts whenever files under contracts/ or packages/ change
+    #[arg(short, long)]
+    pub watch: bool,
 }
 
 impl Executable for BuildCommand {
@@ -27,55 +53,246 @@ impl Executable for BuildCommand {
         let project_root = project_root.unwrap();
         let config = config.unwrap();
 
+        // `None` builds the whole workspace
+        let contracts = self.selected_contracts(&project_root)?;
+        if self.watch {
+            return self.watch(&project_root, &config, contracts);
+        }
+        self.build(&project_root, &config, contracts)
+    }
+}
+
+impl BuildCommand {
+    /// Builds the given contracts, or the whole workspace
+    pub fn build(
+        &self,
+        project_root: &Path,
+        config: &ProjectConfig,
+        contracts: Option<Vec<ContractCrate>>,
+    ) -> Result<(), WarpError> {
+        if let Some(contracts) = &contracts {
+            if contracts.is_empty() {
+                println!("{}", "Nothing to build.".bright_green());
+                return Ok(());
+            }
+            println!(
+                "Building: {}",
+                contracts
+                    .iter()
+                    .map(|x| x.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+                    .bright_blue()
+            );
+        }
+
         if self.optimized {
-            let rename_files: bool;
-            let cmd_str = match config.tooling.optimizer_backend.as_str() {
-                "cw-optimizoor" => {
-                    rename_files = true;
-                    format!("cargo cw-optimizoor .")
+            let optimizer = optimizer::optimizer(&config.tooling)?;
+            let toolchain = optimizer.toolchain();
+            let mut manifest = BuildManifest::load(project_root)?;
+            let selected = match &contracts {
+                Some(x) => x.clone(),
+                None => workspace::contract_crates(project_root)?,
+            };
+            let mut inputs = HashMap::new();
+            let mut changed = vec![];
+            for contract in selected.iter() {
+                let hash = workspace::input_hash(project_root, contract, &toolchain)?;
+                if self.force || !manifest.is_up_to_date(project_root, contract, &hash)? {
+                    changed.push(contract.clone());
                 }
-                _ => {
-                    rename_files = false;
-                    format!("docker run --rm -v {0}:/code --mount type=volume,source={1}_cache,target=/code/target --mount type=volume,source=registry_cache,target=/usr/local/cargo/registry cosmwasm/optimizer:0.16.0", 
-                    &project_root.to_str().unwrap(),
-                    &project_root.to_str().unwrap().rsplit("/").next().unwrap())
+                inputs.insert(contract.name.clone(), hash);
+            }
+            if changed.is_empty() && !selected.is_empty() {
+                println!(
+                    "{} {}",
+                    "Up to date.".bright_green(),
+                    "Nothing changed since the last build (use --force to rebuild)".dimmed()
+                );
+                return Ok(());
+            }
+            if changed.len() < selected.len() {
+                println!(
+                    "Skipping unchanged: {}",
+                    selected
+                        .iter()
+                        .filter(|x| !changed.iter().any(|changed| changed.name == x.name))
+                        .map(|x| x.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+            let contracts = if !optimizer.builds_single_contracts() {
+                if contracts.is_some() || changed.len() < selected.len() {
+                    println!(
+                        "{} {}",
+                        "Warning!".bright_yellow(),
+                        "The optimizer can't build single contracts, building the whole workspace"
+                            .yellow()
+                    );
                 }
+                None
+            } else if contracts.is_none() && changed.len() == selected.len() {
+                None
+            } else {
+                Some(changed)
             };
-            let cmd_tokens = cmd_str.split(" ").collect::<Vec<&str>>();
-            let cmd_name = cmd_tokens.get(0).unwrap();
-            let cmd_args = cmd_tokens.iter().skip(1).map(|x| *x).collect::<Vec<&str>>();
+            optimizer.build(project_root, contracts.as_deref())?;
+            optimizer::normalize_artifacts(project_root)?;
 
-            Command::new(cmd_name)
-                .current_dir(&project_root)
-                .args(cmd_args)
-                .spawn()?
-                .wait()?;
-            if rename_files {
-                let artifacts = project_root.clone().join("artifacts");
-                let dir = std::fs::read_dir(&artifacts)?;
-                // TODO: filter_map()
-                let files_to_rename = dir
-                    .filter(|x| x.is_ok())
-                    .map(|x| x.unwrap())
-                    .map(|x| x.file_name())
-                    .filter(|x| x.to_str().unwrap().contains("-x86_64"))
-                    .collect::<Vec<_>>();
-                for file in files_to_rename {
-                    let new_name = artifacts
-                        .clone()
-                        .join(file.to_str().unwrap().replace("-x86_64", ""));
-                    std::fs::rename(artifacts.clone().join(&file), new_name)?;
+            let built = match contracts {
+                Some(x) => x,
+                None => workspace::contract_crates(project_root)?,
+            };
+            for contract in built.iter() {
+                if !inputs.contains_key(&contract.name) {
+                    let hash = workspace::input_hash(project_root, contract, &toolchain)?;
+                    inputs.insert(contract.name.clone(), hash);
                 }
             }
+            let previous = manifest.clone();
+            manifest.record(project_root, &built, &optimizer.environment(), &inputs)?;
+            manifest.save(project_root)?;
+            print_sizes(&built, &previous, &manifest, config)?;
         } else {
-            let cmd = Command::new("cargo")
+            let status = Command::new("cargo")
+                .current_dir(project_root)
                 .arg("build")
                 .arg("--target")
                 .arg("wasm32-unknown-unknown")
+                .args(
+                    contracts
+                        .iter()
+                        .flatten()
+                        .flat_map(|x| ["-p".to_owned(), x.name.clone()]),
+                )
                 .env("RUSTFLAGS", "-C link-arg=-s")
-                .spawn();
-            cmd?.wait()?;
+                .spawn()?
+                .wait()?;
+            if !status.success() {
+                return Err(WarpError::BuildFailed(status));
+            }
         }
         Ok(())
     }
+
+    /// Builds the contracts, then rebuilds the ones affected by every change to the sources
+    fn watch(
+        &self,
+        project_root: &Path,
+        config: &ProjectConfig,
+        contracts: Option<Vec<ContractCrate>>,
+    ) -> Result<(), WarpError> {
+        let watcher = WorkspaceWatcher::new(project_root)?;
+        watcher::run_step("Build", || self.build(project_root, config, contracts));
+        loop {
+            println!("{}", "Watching for changes... (Ctrl+C to stop)".dimmed());
+            let changed = watcher.wait()?;
+            // `--package` limits what is rebuilt, `--changed` only applies to the first build
+            let mut affected =
+                workspace::changed_in_files(workspace::contract_crates(project_root)?, project_root, &changed);
+            if !self.packages.is_empty() {
+                affected.retain(|x| self.packages.iter().any(|name| x.is_named(name)));
+            }
+            if affected.is_empty() {
+                continue;
+            }
+            watcher::print_changes(&changed);
+            watcher::run_step("Build", || self.build(project_root, config, Some(affected)));
+        }
+    }
+
+    /// The contracts selected with `--package` and `--changed`, or `None` for the whole workspace
+    fn selected_contracts(
+        &self,
+        project_root: &Path,
+    ) -> Result<Option<Vec<ContractCrate>>, WarpError> {
+        if self.packages.is_empty() && self.changed.is_none() {
+            return Ok(None);
+        }
+        let mut contracts = workspace::contract_crates(project_root)?;
+        if let Some(name) = self
+            .packages
+            .iter()
+            .find(|name| !contracts.iter().any(|x| x.is_named(name)))
+        {
+            return Err(WarpError::UnknownContract(name.clone()));
+        }
+        if !self.packages.is_empty() {
+            contracts.retain(|x| self.packages.iter().any(|name| x.is_named(name)));
+        }
+        let contracts = match self.changed.as_deref() {
+            None => contracts,
+            Some("") => workspace::changed_since_build(project_root, contracts, |x| {
+                self.artifact_path(project_root, x)
+            })?,
+            Some(git_ref) => workspace::changed_in_files(
+                contracts,
+                project_root,
+                &git::changed_files(project_root, git_ref)?,
+            ),
+        };
+        Ok(Some(contracts))
+    }
+
+    fn artifact_path(&self, project_root: &Path, contract: &ContractCrate) -> PathBuf {
+        let file = format!("{}.wasm", contract.artifact_name());
+        if self.optimized {
+            project_root.join("artifacts").join(file)
+        } else {
+            project_root
+                .join("target/wasm32-unknown-unknown/debug")
+                .join(file)
+        }
+    }
+}
+
+/// Prints the size of the built artifacts with the change since the previous build, and fails
+/// if any of them is over its `max_size` budget
+fn print_sizes(
+    built: &[ContractCrate],
+    previous: &BuildManifest,
+    manifest: &BuildManifest,
+    config: &ProjectConfig,
+) -> Result<(), WarpError> {
+    let rows: Vec<(String, u64, Option<u64>, Option<u64>)> = built
+        .iter()
+        .filter_map(|contract| {
+            let name = format!("{}.wasm", contract.artifact_name());
+            let size = manifest.contracts.get(&name)?.size;
+            let previous = previous.contracts.get(&name).map(|x| x.size);
+            Some((name, size, previous, config.max_size(&contract.artifact_name())))
+        })
+        .collect();
+    if rows.is_empty() {
+        return Ok(());
+    }
+    let width = rows.iter().map(|x| x.0.len()).max().unwrap_or_default();
+    let mut over_budget = vec![];
+    println!("Artifact sizes:");
+    for (name, size, previous, budget) in rows.iter() {
+        let delta = wasm_size::format_delta(*size, *previous);
+        let budget = match budget {
+            Some(budget) if size > budget => {
+                over_budget.push(name.clone());
+                format!("over budget of {}", wasm_size::format_size(*budget))
+                    .bright_red()
+                    .to_string()
+            }
+            Some(budget) => format!("{}% of budget", size * 100 / budget),
+            None => String::new(),
+        };
+        println!(
+            " {} {:<width$}  {:>10}  {:>10}  {}",
+            "=>".bright_yellow(),
+            name.bright_blue(),
+            wasm_size::format_size(*size),
+            delta,
+            budget
+        );
+    }
+    if !over_budget.is_empty() {
+        return Err(WarpError::SizeBudgetExceeded(over_budget.join(", ")));
+    }
+    Ok(())
 }
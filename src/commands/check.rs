@@ -0,0 +1,111 @@
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    chains::chain_profile::ChainProfile,
+    error::WarpError,
+    executable::Executable,
+    utils::{
+        project_config::ProjectConfig,
+        wasm_check,
+    },
+};
+
+#[derive(Args)]
+pub struct CheckCommand {
+    /// The artifacts to check (defaults to artifacts/*.wasm)
+    pub artifacts: Vec<PathBuf>,
+}
+
+impl Executable for CheckCommand {
+    fn execute(
+        &self,
+        project_root: Option<PathBuf>,
+        config: Option<ProjectConfig>,
+        profile: &Box<dyn ChainProfile>,
+    ) -> Result<(), WarpError> {
+        if project_root.is_none() {
+            return Err(WarpError::ProjectFileNotFound);
+        };
+        let project_root = project_root.unwrap();
+        let config = config.unwrap();
+
+        let artifacts = if self.artifacts.is_empty() {
+            let dir = project_root.join("artifacts");
+            if !dir.exists() {
+                return Err(WarpError::ArtifactNotFound(dir));
+            }
+            let mut artifacts: Vec<PathBuf> = std::fs::read_dir(dir)?
+                .filter_map(|x| x.ok())
+                .map(|x| x.path())
+                .filter(|x| x.extension().is_some_and(|x| x == "wasm"))
+                .collect();
+            artifacts.sort();
+            artifacts
+        } else {
+            self.artifacts.clone()
+        };
+        check_artifacts(&project_root, &artifacts, &config, profile.as_ref())
+    }
+}
+
+/// Checks the artifacts (relative to the workspace) against the chain of the active network
+/// and prints a report for each of them
+pub fn check_artifacts(
+    project_root: &Path,
+    artifacts: &[PathBuf],
+    config: &ProjectConfig,
+    profile: &dyn ChainProfile,
+) -> Result<(), WarpError> {
+    let chain = profile.chain_capabilities();
+    println!(
+        "Checking artifacts for {} ({}, up to {}, at most {} KiB)...",
+        config.network.chain_id.bright_blue(),
+        profile.get_profile_name(),
+        chain.cosmwasm_version(),
+        chain.max_wasm_size / 1024
+    );
+    let mut failed = 0;
+    for artifact in artifacts {
+        let display = artifact.strip_prefix(project_root).unwrap_or(artifact);
+        let report = wasm_check::check_wasm(
+            &std::fs::read(project_root.join(artifact))?,
+            &chain,
+            &config.network.chain_id,
+        )?;
+        let capabilities = if report.capabilities.is_empty() {
+            "none".to_owned()
+        } else {
+            report
+                .capabilities
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        println!(
+            " {} {} ({} KiB) - {}, capabilities: {}",
+            "=>".bright_yellow(),
+            display.display().bright_blue(),
+            report.size.div_ceil(1024),
+            if report.is_ok() {
+                "ok".bright_green().to_string()
+            } else {
+                "FAILED".bright_red().to_string()
+            },
+            capabilities
+        );
+        for error in report.errors.iter() {
+            println!("     - {}", error.bright_red());
+        }
+        if !report.is_ok() {
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        return Err(WarpError::WasmCheckFailed(failed));
+    }
+    Ok(())
+}
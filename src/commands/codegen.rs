@@ -0,0 +1,106 @@
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+use owo_colors::OwoColorize;
+
+use crate::{
+    chains::chain_profile::ChainProfile,
+    error::WarpError,
+    executable::Executable,
+    utils::{codegen_ts, contract_schema::ContractSchema, project_config::ProjectConfig},
+};
+
+const DEFAULT_TS_OUT: &str = "tests/src/contracts";
+
+#[derive(Args)]
+pub struct CodegenCommand {
+    /// Codegen Subcommand
+    #[command(subcommand)]
+    subcommand: CodegenSubcommand,
+}
+
+#[derive(Subcommand)]
+pub enum CodegenSubcommand {
+    /// Generate TypeScript message types and cosmjs clients
+    Ts(CodegenTsArgs),
+}
+
+#[derive(Args, Clone)]
+pub struct CodegenTsArgs {
+    /// Output directory, relative to the workspace (defaults to `codegen.ts_out` or
+    /// `tests/src/contracts`)
+    #[arg(long, short)]
+    pub out: Option<PathBuf>,
+    /// Only generate code for these contracts
+    #[arg(long = "contract", short = 'c')]
+    pub contracts: Vec<String>,
+}
+
+impl Executable for CodegenCommand {
+    fn execute(
+        &self,
+        project_root: Option<PathBuf>,
+        config: Option<ProjectConfig>,
+        _profile: &Box<dyn ChainProfile>,
+    ) -> Result<(), WarpError> {
+        if project_root.is_none() {
+            return Err(WarpError::ProjectFileNotFound);
+        };
+        let project_root = project_root.unwrap();
+        let config = config.unwrap();
+
+        match &self.subcommand {
+            CodegenSubcommand::Ts(args) => {
+                let schemas = ContractSchema::load_all(&project_root)?;
+                if schemas.is_empty() {
+                    return Err(WarpError::NoContractSchemas(project_root.join("schema")));
+                }
+                for name in args.contracts.iter() {
+                    if !schemas.iter().any(|x| is_named(x, name)) {
+                        return Err(WarpError::UnknownContract(name.clone()));
+                    }
+                }
+                let out = match &args.out {
+                    Some(x) => x.clone(),
+                    None => PathBuf::from(
+                        config
+                            .codegen
+                            .and_then(|x| x.ts_out)
+                            .unwrap_or(DEFAULT_TS_OUT.to_owned()),
+                    ),
+                };
+                let out_dir = project_root.join(out);
+                std::fs::create_dir_all(&out_dir)?;
+
+                for schema in schemas.iter() {
+                    if !args.contracts.is_empty()
+                        && !args.contracts.iter().any(|x| is_named(schema, x))
+                    {
+                        continue;
+                    }
+                    let path = out_dir.join(codegen_ts::file_name(schema));
+                    std::fs::write(&path, codegen_ts::render(schema))?;
+                    println!(
+                        " {} {}",
+                        "=>".bright_yellow(),
+                        path.strip_prefix(&project_root).unwrap_or(&path).display()
+                    );
+                }
+                // The index always covers every contract, so that filtered runs don't drop exports
+                let path = out_dir.join("index.ts");
+                std::fs::write(&path, codegen_ts::render_index(&schemas))?;
+                println!(
+                    " {} {}",
+                    "=>".bright_yellow(),
+                    path.strip_prefix(&project_root).unwrap_or(&path).display()
+                );
+                println!("{}", "Done.".bright_green());
+                Ok(())
+            }
+        }
+    }
+}
+
+fn is_named(schema: &ContractSchema, name: &str) -> bool {
+    schema.contract_name.replace('-', "_") == name.replace('-', "_")
+}
@@ -41,6 +41,8 @@ pub enum OptimizerBackend {
     Default,
     /// Use 'cw-optimizoor' which doesn't require docker (it needs to be installed and in $PATH)
     CwOptimizoor,
+    /// Use the local toolchain: `cargo build --release` and `wasm-opt` (it needs to be in $PATH)
+    Local,
 }
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Deserialize)]
@@ -63,7 +65,9 @@ impl Executable for ConfigCommand {
         if project_root.is_none() {
             return Err(WarpError::ProjectFileNotFound);
         };
-        let mut config = config.unwrap().clone();
+        let config = config.unwrap();
+        // Environment overrides are only applied in memory, so changes are made to the file as-is
+        let (_, mut raw_config) = ProjectConfig::parse_project_config()?;
 
         let modify_values: bool;
         let args = match &self.subcommand {
@@ -79,9 +83,10 @@ impl Executable for ConfigCommand {
 
         if let Some(x) = &args.optimizer_backend {
             if modify_values {
-                config.tooling.optimizer_backend = match x {
+                raw_config.tooling.optimizer_backend = match x {
                     OptimizerBackend::Default => "default",
                     OptimizerBackend::CwOptimizoor => "cw-optimizoor",
+                    OptimizerBackend::Local => "local",
                 }
                 .to_owned();
             }
@@ -89,7 +94,7 @@ impl Executable for ConfigCommand {
                 " {} {}: {}",
                 "=>".bright_yellow(),
                 "Optimizer Backend".bright_blue(),
-                config.tooling.optimizer_backend.bright_green()
+                raw_config.tooling.optimizer_backend.bright_green()
             );
         }
 
@@ -97,7 +102,16 @@ impl Executable for ConfigCommand {
         if let Some(x) = &args.network {
             if modify_values {
                 let params = profile.network_params(&x);
-                config.network = params;
+                match &config.active_environment {
+                    Some(env) => {
+                        raw_config
+                            .environments
+                            .entry(env.clone())
+                            .or_default()
+                            .network = Some(params)
+                    }
+                    None => raw_config.network = params,
+                }
             }
             println!(
                 "{} {}: {}",
@@ -108,7 +122,7 @@ impl Executable for ConfigCommand {
         }
 
         if modify_values {
-            config.save_project_config()?;
+            raw_config.save_project_config()?;
         }
         Ok(())
     }
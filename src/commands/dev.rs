@@ -0,0 +1,166 @@
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    chains::chain_profile::ChainProfile,
+    commands::{autodeploy::AutoDeployCommand, schema::SchemaCommand, BuildCommand},
+    error::WarpError,
+    executable::Executable,
+    utils::{
+        project_config::ProjectConfig,
+        watcher::{self, WorkspaceWatcher},
+        workspace::{self, ContractCrate},
+    },
+};
+
+#[derive(Args)]
+pub struct DevCommand {
+    /// Only rebuild the contracts and regenerate the schema, don't deploy them
+    #[arg(long)]
+    pub no_deploy: bool,
+    /// Deploy even if the network isn't a local node
+    #[arg(long)]
+    pub allow_remote: bool,
+    /// Don't ask for a keyring password (see also WARP_KEYRING_PASSWORD)
+    #[arg(long)]
+    pub no_password: bool,
+}
+
+impl Executable for DevCommand {
+    fn execute(
+        &self,
+        project_root: Option<PathBuf>,
+        config: Option<ProjectConfig>,
+        profile: &Box<dyn ChainProfile>,
+    ) -> Result<(), WarpError> {
+        if project_root.is_none() {
+            return Err(WarpError::ProjectFileNotFound);
+        };
+        let project_root = project_root.unwrap();
+        let config = config.unwrap();
+
+        if !self.no_deploy && !self.allow_remote && !is_local(&config.network.rpc_url) {
+            return Err(WarpError::NotLocalNetwork(config.network.rpc_url.clone()));
+        }
+        if !self.no_deploy {
+            println!(
+                "Deploying to {} ({})",
+                config.network.chain_id.bright_blue(),
+                config.network.rpc_url
+            );
+        }
+
+        // Unchanged code is reused and contracts already running it aren't migrated
+        let deploy = AutoDeployCommand {
+            command: None,
+            rebuild: false,
+            plan: false,
+            no_password: self.no_password,
+            generate_only: false,
+            skip_check: false,
+            skip_unchanged: true,
+            await_proposal: None,
+            proposal_timeout: 3600,
+        };
+        let deploy = || deploy.execute(Some(project_root.clone()), Some(config.clone()), profile);
+
+        let watcher = WorkspaceWatcher::new(&project_root)?;
+        let mut iteration = 1;
+        println!(
+            "\n{}",
+            format!("[{iteration}] Initial build").bright_yellow()
+        );
+        self.iterate(&project_root, &config, None, deploy);
+        loop {
+            println!("{}", "Watching for changes... (Ctrl+C to stop)".dimmed());
+            let changed = watcher.wait()?;
+            let affected = workspace::changed_in_files(
+                workspace::contract_crates(&project_root)?,
+                &project_root,
+                &changed,
+            );
+            if affected.is_empty() {
+                continue;
+            }
+            iteration += 1;
+            watcher::print_changes(&changed);
+            println!(
+                "{}",
+                format!(
+                    "[{iteration}] {}",
+                    affected
+                        .iter()
+                        .map(|x| x.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+                .bright_yellow()
+            );
+            self.iterate(&project_root, &config, Some(affected), deploy);
+        }
+    }
+}
+
+impl DevCommand {
+    /// Builds the contracts (all of them for `None`), regenerates their schema and runs `deploy`
+    fn iterate(
+        &self,
+        project_root: &Path,
+        config: &ProjectConfig,
+        contracts: Option<Vec<ContractCrate>>,
+        deploy: impl FnOnce() -> Result<(), WarpError>,
+    ) {
+        let build = BuildCommand {
+            optimized: true,
+            ..Default::default()
+        };
+        if !watcher::run_step("Build", || {
+            build.build(project_root, config, contracts.clone())
+        }) {
+            return;
+        }
+        watcher::run_step("Schema", || {
+            let contracts = match &contracts {
+                Some(x) => x.clone(),
+                None => workspace::contract_crates(project_root)?,
+            };
+            SchemaCommand::generate(project_root, contracts.into_iter().map(|x| x.dir))
+        });
+        if self.no_deploy {
+            return;
+        }
+        watcher::run_step("Deploy", deploy);
+    }
+}
+
+/// Whether the RPC endpoint is a node on this machine, like the one started by `warp node`
+fn is_local(rpc_url: &str) -> bool {
+    let host = rpc_url
+        .split("://")
+        .last()
+        .unwrap_or_default()
+        .split('/')
+        .next()
+        .unwrap_or_default();
+    let host = match host.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|x| x.is_ascii_digit()) => host,
+        _ => host,
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "0.0.0.0" | "[::1]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_local_nodes() {
+        assert!(is_local("http://localhost:26657"));
+        assert!(is_local("tcp://127.0.0.1:26657/"));
+        assert!(is_local("http://[::1]:26657"));
+        assert!(!is_local("https://rpc.mainnet.archway.io:443"));
+        assert!(!is_local("https://localhost.example.com"));
+    }
+}
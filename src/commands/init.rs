@@ -30,11 +30,11 @@ impl ChainParam {
     pub fn get_chain_profile(&self) -> Box<dyn ChainProfile> {
         match self {
             ChainParam::Archway => Box::new(crate::chains::archway::ArchwayProfile),
-            ChainParam::Xion => Box::new(crate::chains::xion::XionProfile),
+            ChainParam::Xion => Box::new(crate::chains::xion::XionProfile::default()),
             ChainParam::Scrt => Box::new(crate::chains::secret::SecretNetworkProfile),
             ChainParam::Sei => Box::new(crate::chains::sei::SeiProfile),
             ChainParam::Injective => Box::new(crate::chains::injective::InjectiveProfile),
-            ChainParam::Juno => Box::new(crate::chains::juno::JunoProfile),
+            ChainParam::Juno => Box::new(crate::chains::juno::JunoProfile::default()),
         }
     }
 }
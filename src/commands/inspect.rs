@@ -0,0 +1,122 @@
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    chains::chain_profile::ChainProfile,
+    error::WarpError,
+    executable::Executable,
+    utils::{
+        project_config::ProjectConfig,
+        wasm_size::{self, format_size},
+        workspace,
+    },
+};
+
+#[derive(Args)]
+pub struct InspectCommand {
+    /// The artifact to inspect (a path or a contract name)
+    pub artifact: String,
+    /// Show which functions, data segments and sections take up the space
+    #[arg(long)]
+    pub size: bool,
+    /// Number of functions and data segments to list
+    #[arg(long, default_value_t = 20)]
+    pub top: usize,
+}
+
+impl Executable for InspectCommand {
+    fn execute(
+        &self,
+        project_root: Option<PathBuf>,
+        config: Option<ProjectConfig>,
+        _profile: &Box<dyn ChainProfile>,
+    ) -> Result<(), WarpError> {
+        if project_root.is_none() {
+            return Err(WarpError::ProjectFileNotFound);
+        };
+        let project_root = project_root.unwrap();
+        let config = config.unwrap();
+
+        let path = Self::artifact_path(&project_root, &self.artifact)?;
+        let breakdown = wasm_size::size_breakdown(&std::fs::read(&path)?)?;
+        let size = breakdown.size as u64;
+        let artifact_name = path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        println!(
+            "{} - {}",
+            path.strip_prefix(&project_root)
+                .unwrap_or(&path)
+                .display()
+                .bright_blue(),
+            format_size(size).bright_green()
+        );
+        if let Some(budget) = config.max_size(&artifact_name) {
+            println!(
+                "Budget: {} ({}% used)",
+                format_size(budget),
+                size * 100 / budget
+            );
+        }
+        println!("Functions: {}", breakdown.functions.len());
+        println!("Exports: {}", breakdown.exports.join(", "));
+        if !self.size {
+            return Ok(());
+        }
+
+        println!("\nSections:");
+        Self::print_entries(&breakdown.sections, size, breakdown.sections.len());
+        println!("\nLargest functions:");
+        Self::print_entries(&breakdown.functions, size, self.top);
+        if !breakdown.data.is_empty() {
+            println!("\nLargest data segments:");
+            Self::print_entries(&breakdown.data, size, self.top);
+        }
+        Ok(())
+    }
+}
+
+impl InspectCommand {
+    /// The path of the artifact, `artifacts/<name>.wasm` when given a contract name
+    fn artifact_path(project_root: &Path, artifact: &str) -> Result<PathBuf, WarpError> {
+        let path = project_root.join(artifact);
+        if path.is_file() {
+            return Ok(path);
+        }
+        let contract = workspace::contract_crates(project_root)?
+            .into_iter()
+            .find(|x| x.is_named(artifact))
+            .ok_or_else(|| WarpError::UnknownContract(artifact.to_owned()))?;
+        let path = project_root
+            .join("artifacts")
+            .join(format!("{}.wasm", contract.artifact_name()));
+        if !path.exists() {
+            return Err(WarpError::ArtifactNotFound(path));
+        }
+        Ok(path)
+    }
+
+    fn print_entries(entries: &[(String, usize)], total: u64, top: usize) {
+        for (name, size) in entries.iter().take(top) {
+            println!(
+                " {:>10}  {:>5.1}%  {}",
+                format_size(*size as u64),
+                *size as f64 * 100.0 / total as f64,
+                name
+            );
+        }
+        if entries.len() > top {
+            let rest: usize = entries.iter().skip(top).map(|x| x.1).sum();
+            println!(
+                " {:>10}  {:>5.1}%  {}",
+                format_size(rest as u64),
+                rest as f64 * 100.0 / total as f64,
+                format!("({} more)", entries.len() - top).dimmed()
+            );
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+use clap::{Args, Subcommand};
+use owo_colors::OwoColorize;
+
+use crate::{
+    chains::chain_profile::ChainProfile,
+    error::WarpError,
+    executable::Executable,
+    utils::{keystore::NativeKeystore, project_config::ProjectConfig},
+};
+
+#[derive(Args)]
+pub struct KeysCommand {
+    /// Keys Subcommand
+    #[command(subcommand)]
+    subcommand: KeysSubcommand,
+}
+
+#[derive(Subcommand)]
+pub enum KeysSubcommand {
+    /// List all keys in the keyring
+    List,
+    /// Create a new key
+    Add(KeysAddArgs),
+    /// Import a key from a mnemonic or a key file created with `warp keys export`
+    Import(KeysImportArgs),
+    /// Export a key as an ASCII-armored private key (or the mnemonic of a native key)
+    Export(KeysNameArgs),
+    /// Show a key (defaults to the deployment account)
+    Show(KeysShowArgs),
+    /// Delete a key from the keyring
+    Delete(KeysDeleteArgs),
+}
+
+#[derive(Args, Clone)]
+pub struct KeysAddArgs {
+    /// Name of the key
+    #[arg(required = true)]
+    pub name: String,
+}
+
+#[derive(Args, Clone)]
+pub struct KeysImportArgs {
+    /// Name of the key
+    #[arg(required = true)]
+    pub name: String,
+    /// Key file created with `warp keys export` (prompts for a mnemonic if omitted)
+    pub file: Option<PathBuf>,
+}
+
+#[derive(Args, Clone)]
+pub struct KeysNameArgs {
+    /// Name of the key
+    #[arg(required = true)]
+    pub name: String,
+}
+
+#[derive(Args, Clone)]
+pub struct KeysShowArgs {
+    /// Name of the key
+    pub name: Option<String>,
+    /// Only print the address
+    #[arg(long, short)]
+    pub address: bool,
+}
+
+#[derive(Args, Clone)]
+pub struct KeysDeleteArgs {
+    /// Name of the key
+    #[arg(required = true)]
+    pub name: String,
+    /// Skip the confirmation prompt
+    #[arg(long, short)]
+    pub yes: bool,
+}
+
+impl Executable for KeysCommand {
+    fn execute(
+        &self,
+        project_root: Option<PathBuf>,
+        config: Option<ProjectConfig>,
+        profile: &Box<dyn ChainProfile>,
+    ) -> Result<(), WarpError> {
+        if project_root.is_none() {
+            return Err(WarpError::ProjectFileNotFound);
+        };
+        let project_root = project_root.unwrap();
+        let config = config.unwrap();
+
+        match config.native_keystore_dir(&project_root) {
+            Some(dir) => self.native(&NativeKeystore::new(dir), &config, profile.as_ref())?,
+            None => self.cli(&config, profile.as_ref())?,
+        }
+
+        let created = match &self.subcommand {
+            KeysSubcommand::Add(x) => Some(&x.name),
+            KeysSubcommand::Import(x) => Some(&x.name),
+            _ => None,
+        };
+        if let Some(name) = created {
+            if &config.autodeploy.account_id != name {
+                Self::offer_account_id(name, &config)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl KeysCommand {
+    fn cli(&self, config: &ProjectConfig, profile: &dyn ChainProfile) -> Result<(), WarpError> {
+        let args: Vec<String> = match &self.subcommand {
+            KeysSubcommand::List => vec!["list".to_owned()],
+            KeysSubcommand::Add(x) => vec!["add".to_owned(), x.name.clone()],
+            KeysSubcommand::Import(x) => match &x.file {
+                Some(file) => vec![
+                    "import".to_owned(),
+                    x.name.clone(),
+                    file.to_string_lossy().to_string(),
+                ],
+                None => vec!["add".to_owned(), x.name.clone(), "--recover".to_owned()],
+            },
+            KeysSubcommand::Export(x) => vec!["export".to_owned(), x.name.clone()],
+            KeysSubcommand::Show(x) => {
+                let name = x.name.as_ref().unwrap_or(&config.autodeploy.account_id);
+                if name.is_empty() {
+                    return Err(WarpError::UnspecifiedWallet);
+                }
+                let mut args = vec!["show".to_owned(), name.clone()];
+                if x.address {
+                    args.push("--address".to_owned());
+                }
+                args
+            }
+            KeysSubcommand::Delete(x) => {
+                let mut args = vec!["delete".to_owned(), x.name.clone()];
+                if x.yes {
+                    args.push("--yes".to_owned());
+                }
+                args
+            }
+        };
+
+        let status = Command::new(profile.get_executable_name())
+            .arg("keys")
+            .args(args)
+            .args(config.keyring_cli_args())
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()?;
+        if !status.success() {
+            return Err(WarpError::KeysCommandFailed(status));
+        }
+        Ok(())
+    }
+
+    fn native(
+        &self,
+        keystore: &NativeKeystore,
+        config: &ProjectConfig,
+        profile: &dyn ChainProfile,
+    ) -> Result<(), WarpError> {
+        // Keys can be managed for any chain, but their addresses only for the supported ones
+        let show = |name: &str| match profile.address_prefix() {
+            Some(prefix) => keystore.show(name, &prefix, profile.coin_type()),
+            None => Err(WarpError::UnsupportedFeature(
+                "Addresses of native keys".to_owned(),
+                profile.get_profile_name(),
+            )),
+        };
+        match &self.subcommand {
+            KeysSubcommand::List => {
+                for name in keystore.list()? {
+                    let address = show(&name).map(|x| x.address).unwrap_or_default();
+                    println!(
+                        " {} {} {}",
+                        "=>".bright_yellow(),
+                        name.bright_blue(),
+                        address
+                    );
+                }
+            }
+            KeysSubcommand::Add(x) => {
+                let mnemonic = keystore.add(&x.name)?;
+                println!("{} {}", "Created key:".bright_green(), x.name.bright_blue());
+                if let Ok(key) = show(&x.name) {
+                    println!("Address: {}", key.address);
+                }
+                println!(
+                    "\n{}\n{}\n",
+                    "Write down the mnemonic, it's the only way to recover the key:"
+                        .bright_yellow(),
+                    mnemonic
+                );
+            }
+            KeysSubcommand::Import(x) => {
+                let mnemonic = match &x.file {
+                    Some(file) => std::fs::read_to_string(file)?,
+                    None => rpassword::prompt_password("Enter the mnemonic of the key:")?,
+                };
+                keystore.import(&x.name, &mnemonic)?;
+                println!(
+                    "{} {}",
+                    "Imported key:".bright_green(),
+                    x.name.bright_blue()
+                );
+            }
+            // The mnemonic is the only secret of a native key, so that's what is exported
+            KeysSubcommand::Export(x) => println!("{}", keystore.mnemonic(&x.name)?),
+            KeysSubcommand::Show(x) => {
+                let name = x.name.as_ref().unwrap_or(&config.autodeploy.account_id);
+                if name.is_empty() {
+                    return Err(WarpError::UnspecifiedWallet);
+                }
+                let key = show(name)?;
+                if x.address {
+                    println!("{}", key.address);
+                } else {
+                    println!(
+                        "Name: {}\nAddress: {}\nPubkey: {}",
+                        key.name, key.address, key.pubkey
+                    );
+                }
+            }
+            KeysSubcommand::Delete(x) => {
+                if !x.yes && !Self::confirm(&format!("Delete the key '{}'?", x.name.bright_blue()))?
+                {
+                    return Ok(());
+                }
+                keystore.delete(&x.name)?;
+                println!("{}", "Done.".bright_green());
+            }
+        }
+        Ok(())
+    }
+
+    fn confirm(question: &str) -> Result<bool, WarpError> {
+        print!("{} {} [y/N] ", "?".bright_yellow(), question);
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+    }
+
+    fn offer_account_id(name: &str, config: &ProjectConfig) -> Result<(), WarpError> {
+        let setting = match &config.active_environment {
+            Some(env) => format!("environments.{env}.account_id"),
+            None => "autodeploy.account_id".to_owned(),
+        };
+        if !Self::confirm(&format!(
+            "Use '{}' as the deployment account ({})?",
+            name.bright_blue(),
+            setting
+        ))? {
+            return Ok(());
+        }
+        // Environment overrides are only applied in memory, so changes are made to the file as-is
+        let (_, mut raw_config) = ProjectConfig::parse_project_config()?;
+        match config
+            .active_environment
+            .as_ref()
+            .and_then(|x| raw_config.environments.get_mut(x))
+        {
+            Some(env) => env.account_id = Some(name.to_owned()),
+            None => raw_config.autodeploy.account_id = name.to_owned(),
+        }
+        raw_config.save_project_config()?;
+        println!("{}", "Done.".bright_green());
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
 pub mod build;
+pub mod check;
+pub mod dev;
+pub mod inspect;
 pub mod init;
 pub mod new;
 pub mod node;
@@ -6,8 +9,12 @@ pub mod test;
 pub mod autodeploy;
 pub mod config;
 pub mod wasm;
+pub mod keys;
+pub mod tx;
+pub mod verify;
 pub mod frontend;
 pub mod schema;
+pub mod codegen;
 pub mod pipeline;
 
 pub use build::*;
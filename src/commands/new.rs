@@ -23,14 +23,15 @@ impl Executable for NewCommand {
     fn execute(
         &self,
         project_root: Option<PathBuf>,
-        config: Option<ProjectConfig>,
+        _config: Option<ProjectConfig>,
         profile: &Box<dyn ChainProfile>,
     ) -> Result<(), WarpError> {
         if project_root.is_none() {
             return Err(WarpError::ProjectFileNotFound);
         };
         let project_root = project_root.unwrap();
-        let mut config = config.unwrap().clone();
+        // Reload the file so that environment overrides don't get saved into the base config
+        let (_, mut config) = ProjectConfig::parse_project_config()?;
 
         let contract_name = Self::optimize_for_path(&self.name)?;
         let contract_dir = project_root.join("contracts").join(&contract_name);
@@ -42,6 +43,10 @@ impl Executable for NewCommand {
             init_msg: "{ \"owner\": \"$account_id\", \"message\": \"\" }".to_owned(),
             migrate_msg: Some("{}".to_owned()),
             coins: None,
+            admin: None,
+            salt: None,
+            instantiate_permission: None,
+            gas: None,
         };
         config.autodeploy.steps.push(deploy_step);
         profile.new_contract(&contract_name, &contract_dir, &project_root)?;
@@ -19,7 +19,7 @@ impl Executable for CreatePipelineCommand {
     ) -> Result<(), WarpError> {
         let pipeline = Pipeline {
             name: self.name.clone(),
-            config: crate::toolchains::pipeline::PipelineConfig { cli_executable: String::new(), cli_contract_subcommand: String::new(), cli_args: String::new(), cli_args_tx: String::new(), cli_args_network: String::new(), cli_args_store: String::new() }
+            config: crate::toolchains::pipeline::PipelineConfig { cli_executable: String::new(), cli_contract_subcommand: String::new(), cli_args: String::new(), cli_args_tx: String::new(), cli_args_network: String::new(), cli_args_store: String::new(), capabilities: None, max_wasm_size: None }
         };
         let path = std::env::current_dir()?.join(format!("{}.toml", &self.name));
         std::fs::write(path, toml::to_string_pretty(&pipeline)?)?;
@@ -1,15 +1,42 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub use clap::Args;
+use clap::Subcommand;
 use owo_colors::OwoColorize;
 
 use crate::{
-    chains::chain_profile::ChainProfile, error::WarpError, executable::Executable,
-    utils::project_config::ProjectConfig,
+    chains::chain_profile::ChainProfile,
+    error::WarpError,
+    executable::Executable,
+    utils::{
+        contract_schema::ContractSchema,
+        project_config::ProjectConfig,
+        schema_docs::{self, ContractDocs, DocsFormat},
+    },
 };
 
 #[derive(Args)]
-pub struct SchemaCommand {}
+pub struct SchemaCommand {
+    /// Schema Subcommand (generates the schema if omitted)
+    #[command(subcommand)]
+    subcommand: Option<SchemaSubcommand>,
+}
+
+#[derive(Subcommand)]
+pub enum SchemaSubcommand {
+    /// Generate an OpenAPI document and a Markdown or HTML reference for each contract
+    Docs(SchemaDocsArgs),
+}
+
+#[derive(Args, Clone)]
+pub struct SchemaDocsArgs {
+    /// Format of the reference
+    #[arg(long, short, value_enum, default_value = "markdown")]
+    pub format: DocsFormat,
+    /// Output directory, relative to the workspace
+    #[arg(long, short, default_value = "schema/docs")]
+    pub out: PathBuf,
+}
 
 impl Executable for SchemaCommand {
     fn execute(
@@ -23,10 +50,8 @@ impl Executable for SchemaCommand {
         };
         let project_root = project_root.unwrap();
         //let config = config.unwrap();
-
-        let global_schema_dir = project_root.join("schema");
-        if !global_schema_dir.exists() {
-            std::fs::create_dir(&global_schema_dir)?;
+        if let Some(SchemaSubcommand::Docs(args)) = &self.subcommand {
+            return Self::docs(&project_root, args);
         }
 
         let contracts_dir = project_root.join("contracts");
@@ -41,6 +66,55 @@ impl Executable for SchemaCommand {
             }
             Err(_) => None,
         });
+        Self::generate(&project_root, contracts_paths)
+    }
+}
+
+impl SchemaCommand {
+    /// Documents the contracts in `schema/` for integrators
+    fn docs(project_root: &Path, args: &SchemaDocsArgs) -> Result<(), WarpError> {
+        let schemas = ContractSchema::load_all(project_root)?;
+        if schemas.is_empty() {
+            return Err(WarpError::NoContractSchemas(project_root.join("schema")));
+        }
+        let out_dir = project_root.join(&args.out);
+        std::fs::create_dir_all(&out_dir)?;
+
+        let mut files = vec![];
+        for schema in schemas.iter() {
+            let api = serde_json::to_string_pretty(&schema_docs::openapi(schema))?;
+            files.push((format!("{}.openapi.json", schema.contract_name), api));
+            files.push((
+                format!("{}.{}", schema.contract_name, args.format.extension()),
+                ContractDocs::new(schema).render(args.format),
+            ));
+        }
+        files.push((
+            format!("index.{}", args.format.extension()),
+            schema_docs::render_index(&schemas, args.format),
+        ));
+        for (name, contents) in files {
+            let path = out_dir.join(name);
+            std::fs::write(&path, contents)?;
+            println!(
+                " {} {}",
+                "=>".bright_yellow(),
+                path.strip_prefix(project_root).unwrap_or(&path).display()
+            );
+        }
+        println!("{}", "Done.".bright_green());
+        Ok(())
+    }
+
+    /// Generates the schema of the contracts in the given directories into `schema/`
+    pub fn generate(
+        project_root: &Path,
+        contracts_paths: impl Iterator<Item = PathBuf>,
+    ) -> Result<(), WarpError> {
+        let global_schema_dir = project_root.join("schema");
+        if !global_schema_dir.exists() {
+            std::fs::create_dir(&global_schema_dir)?;
+        }
 
         for path in contracts_paths {
             let contract_name = path.file_name().unwrap().to_string_lossy().to_string();
@@ -34,7 +34,10 @@ impl Executable for TestCommand {
 
         // 1. Build the code if requested
         if self.rebuild {
-            let cmd = BuildCommand { optimized: true };
+            let cmd = BuildCommand {
+                optimized: true,
+                ..Default::default()
+            };
             cmd.execute(Some(project_root.clone()), Some(config.clone()), profile)?;
         }
 
@@ -0,0 +1,213 @@
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use clap::{Args, Subcommand};
+use owo_colors::OwoColorize;
+
+use crate::{
+    chains::chain_profile::ChainProfile,
+    cosmos::tx_query::TxQueryResponse,
+    error::WarpError,
+    executable::Executable,
+    utils::{
+        command_util::CommandWithInput,
+        credentials,
+        deployment_result::DeploymentResult,
+        offline_tx::{PendingTxs, TxKind},
+        project_config::ProjectConfig,
+    },
+};
+
+#[derive(Args)]
+pub struct TxCommand {
+    /// Tx Subcommand
+    #[command(subcommand)]
+    subcommand: TxSubcommand,
+    /// Don't ask for a keyring password (see also WARP_KEYRING_PASSWORD)
+    #[arg(long, global = true)]
+    no_password: bool,
+}
+
+#[derive(Subcommand)]
+pub enum TxSubcommand {
+    /// Sign a transaction generated with `--generate-only`
+    Sign(TxSignArgs),
+    /// Broadcast a signed transaction and import its results into Deployment.toml
+    Broadcast(TxBroadcastArgs),
+}
+
+#[derive(Args, Clone)]
+pub struct TxSignArgs {
+    /// The unsigned transaction file (from .warp/txs/)
+    #[arg(required = true)]
+    pub file: PathBuf,
+    #[arg(long, short)]
+    pub from: Option<String>,
+    /// Sign on behalf of this multisig address (combine the signatures with `<cli> tx multisign`)
+    #[arg(long)]
+    pub multisig: Option<String>,
+    /// Output file (defaults to `<name>.signed.json` next to the input)
+    #[arg(long, short)]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Args, Clone)]
+pub struct TxBroadcastArgs {
+    /// The signed transaction file
+    #[arg(required = true)]
+    pub file: PathBuf,
+}
+
+impl Executable for TxCommand {
+    fn execute(
+        &self,
+        project_root: Option<PathBuf>,
+        config: Option<ProjectConfig>,
+        profile: &Box<dyn ChainProfile>,
+    ) -> Result<(), WarpError> {
+        if project_root.is_none() {
+            return Err(WarpError::ProjectFileNotFound);
+        };
+        let project_root = project_root.unwrap();
+        let config = config.unwrap();
+
+        match &self.subcommand {
+            TxSubcommand::Sign(x) => self.sign(x, &project_root, &config, profile.as_ref()),
+            TxSubcommand::Broadcast(x) => Self::broadcast(x, &project_root, &config, profile.as_ref()),
+        }
+    }
+}
+
+impl TxCommand {
+    fn sign(
+        &self,
+        args: &TxSignArgs,
+        project_root: &Path,
+        config: &ProjectConfig,
+        profile: &dyn ChainProfile,
+    ) -> Result<(), WarpError> {
+        let from = args.from.as_ref().unwrap_or(&config.autodeploy.account_id);
+        if from.is_empty() {
+            return Err(WarpError::UnspecifiedWallet);
+        }
+        let out = args.out.clone().unwrap_or_else(|| {
+            let name = args
+                .file
+                .file_name()
+                .map(|x| x.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let prefix = name.split('.').next().unwrap_or_default();
+            args.file.with_file_name(format!("{prefix}.signed.json"))
+        });
+        let password = credentials::keyring_password(self.no_password, project_root, config)?;
+        // Most profiles only add the chain ID to transactions, but signing needs it too
+        let mut common_args = profile.get_common_cli_args(false, true, false, config);
+        if !common_args.iter().any(|x| x == "--chain-id") {
+            common_args.push("--chain-id".to_owned());
+            common_args.push(config.network.chain_id.clone());
+        }
+
+        let mut tx = Command::new(profile.get_executable_name());
+        tx.args(vec!["tx", "sign"])
+            .arg(&args.file)
+            .args(vec!["--from", from])
+            .args(
+                args.multisig
+                    .as_ref()
+                    .map(|x| vec!["--multisig".to_owned(), x.clone()])
+                    .unwrap_or_default(),
+            )
+            .args(common_args)
+            .args(config.keyring_cli_args())
+            .arg("--output-document")
+            .arg(&out)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .stdin(if password.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::inherit()
+            });
+        let output = match &password {
+            Some(pass) => tx.call_process_with_input(pass)?,
+            None => tx.output()?,
+        };
+        if !output.status.success() {
+            return Err(WarpError::UnderlyingCliError(String::from_utf8(output.stderr)?));
+        }
+        println!("{} {}", "Signed:".bright_green(), out.display());
+        Ok(())
+    }
+
+    fn broadcast(
+        args: &TxBroadcastArgs,
+        project_root: &Path,
+        config: &ProjectConfig,
+        profile: &dyn ChainProfile,
+    ) -> Result<(), WarpError> {
+        let out = Command::new(profile.get_executable_name())
+            .args(vec!["tx", "broadcast"])
+            .arg(&args.file)
+            .args(profile.get_common_cli_args(false, true, false, config))
+            .stdin(Stdio::inherit())
+            .output()?;
+        if !out.status.success() {
+            return Err(WarpError::UnderlyingCliError(String::from_utf8(out.stderr)?));
+        }
+        let response: TxQueryResponse = serde_json::from_slice(&out.stdout)?;
+        if response.code != 0 {
+            return Err(WarpError::TxFailed(response.txhash, response.raw_log));
+        }
+        let response = profile.query_tx(&response.txhash, config)?;
+        println!(
+            "{} ({})",
+            "Broadcast.".bright_green(),
+            response.txhash.bright_blue()
+        );
+
+        let mut pending = PendingTxs::load(project_root)?;
+        let (prefix, tx) = match pending.find(&args.file) {
+            Some(x) => x,
+            // Not generated by warp, nothing to import
+            None => return Ok(()),
+        };
+        let mut deployments = if DeploymentResult::exists()? {
+            DeploymentResult::parse()?.1
+        } else {
+            DeploymentResult::default()
+        };
+        match tx.kind {
+            TxKind::Store => {
+                let code_id = profile.get_stored_code_id(&response)?;
+                let record = deployments.record(&tx.deployment, &tx.step);
+                record.code_id = Some(code_id.clone());
+                record.checksum = tx.checksum.clone();
+                println!(
+                    " {} {} - CODE: {}",
+                    "=>".bright_yellow(),
+                    tx.step.bright_blue(),
+                    code_id.bright_green()
+                );
+            }
+            TxKind::Instantiate => {
+                let address = profile.get_initialized_address(&response)?;
+                deployments
+                    .network(&tx.deployment)
+                    .insert(tx.step.clone(), address.clone());
+                println!(
+                    " {} {}: {}",
+                    "=>".bright_yellow(),
+                    tx.step.bright_blue(),
+                    address.bright_cyan()
+                );
+            }
+            TxKind::Migrate | TxKind::Execute => (),
+        }
+        deployments.save()?;
+        pending.txs.remove(&prefix);
+        pending.save(project_root)?;
+        Ok(())
+    }
+}
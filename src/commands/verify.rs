@@ -0,0 +1,288 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use clap::Args;
+use owo_colors::OwoColorize;
+use serde::Serialize;
+
+use crate::{
+    chains::chain_profile::ChainProfile,
+    error::WarpError,
+    executable::Executable,
+    utils::{
+        deployment_result::DeploymentResult,
+        file_util,
+        project_config::{AutoDeployStep, ProjectConfig},
+    },
+};
+
+#[derive(Args)]
+pub struct VerifyCommand {
+    /// Only verify this step (from the Warp.toml file)
+    pub step: Option<String>,
+    /// Write the verification report as JSON to this file
+    #[arg(long, short)]
+    pub out: Option<PathBuf>,
+    /// Verify the deployments on every network in Deployment.toml, not just the active one
+    #[arg(long)]
+    pub all: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct VerifyReport {
+    /// Environment or chain ID of the deployment
+    pub deployment: String,
+    pub chain_id: String,
+    pub verified_at: u64,
+    pub contracts: Vec<ContractVerification>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ContractVerification {
+    pub step: String,
+    pub artifact: String,
+    pub address: Option<String>,
+    pub code_id: Option<String>,
+    pub onchain_checksum: Option<String>,
+    pub local_checksum: Option<String>,
+    /// Checksum from the optimizer's `checksums.txt`
+    pub optimizer_checksum: Option<String>,
+    pub status: VerifyStatus,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum VerifyStatus {
+    Match,
+    Mismatch,
+    NotDeployed,
+    MissingArtifact,
+}
+
+impl Executable for VerifyCommand {
+    fn execute(
+        &self,
+        project_root: Option<PathBuf>,
+        config: Option<ProjectConfig>,
+        profile: &Box<dyn ChainProfile>,
+    ) -> Result<(), WarpError> {
+        if project_root.is_none() {
+            return Err(WarpError::ProjectFileNotFound);
+        };
+        let project_root = project_root.unwrap();
+        let config = config.unwrap();
+        let deployments = if DeploymentResult::exists()? {
+            DeploymentResult::parse()?.1
+        } else {
+            DeploymentResult::default()
+        };
+        let steps: Vec<&AutoDeployStep> = config
+            .autodeploy
+            .steps
+            .iter()
+            .filter(|x| self.step.as_ref().is_none_or(|step| &x.id == step))
+            .collect();
+        if let (Some(step), true) = (&self.step, steps.is_empty()) {
+            return Err(WarpError::ContractIdNotFound(step.clone()));
+        }
+
+        if !self.all {
+            let report = Self::verify_deployment(&steps, &project_root, &config, &deployments, profile.as_ref())?;
+            if let Some(out) = &self.out {
+                std::fs::write(out, serde_json::to_string_pretty(&report)?)?;
+                println!("{} {}", "Report:".bright_green(), out.display());
+            }
+            return Self::check_mismatches(&[report]);
+        }
+
+        let keys: BTreeSet<&String> = deployments
+            .deployment
+            .keys()
+            .chain(deployments.records.keys())
+            .collect();
+        let mut reports = vec![];
+        for key in keys {
+            let Some(config) = Self::network_config(key, &config, &deployments) else {
+                println!(
+                    "{} {} (the network is unknown, add it to the environments in Warp.toml)\n",
+                    "Skipping".bright_yellow(),
+                    key.bright_blue()
+                );
+                continue;
+            };
+            // An unreachable network shouldn't hide the results of the others
+            match Self::verify_deployment(&steps, &project_root, &config, &deployments, profile.as_ref()) {
+                Ok(report) => reports.push(report),
+                Err(e) => println!(" {} {}", "Failed:".bright_red(), e),
+            }
+            println!();
+        }
+        if let Some(out) = &self.out {
+            std::fs::write(out, serde_json::to_string_pretty(&reports)?)?;
+            println!("{} {}", "Report:".bright_green(), out.display());
+        }
+        Self::check_mismatches(&reports)
+    }
+}
+
+impl VerifyCommand {
+    /// Verifies the steps against the deployment of the config's network
+    fn verify_deployment(
+        steps: &[&AutoDeployStep],
+        project_root: &Path,
+        config: &ProjectConfig,
+        deployments: &DeploymentResult,
+        profile: &dyn ChainProfile,
+    ) -> Result<VerifyReport, WarpError> {
+        println!(
+            "Verifying {} ({}):",
+            config.deployment_key().bright_blue(),
+            config.network.chain_id.bright_blue()
+        );
+        let mut report = VerifyReport {
+            deployment: config.deployment_key(),
+            chain_id: config.network.chain_id.clone(),
+            verified_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            contracts: vec![],
+        };
+        for step in steps {
+            let result = Self::verify_step(step, project_root, config, deployments, profile)?;
+            let status = match result.status {
+                VerifyStatus::Match => "match".bright_green().to_string(),
+                VerifyStatus::Mismatch => "MISMATCH".bright_red().to_string(),
+                VerifyStatus::NotDeployed => "not deployed".bright_yellow().to_string(),
+                VerifyStatus::MissingArtifact => "missing artifact".bright_yellow().to_string(),
+            };
+            println!(
+                " {} {} (code {}) - {}",
+                "=>".bright_yellow(),
+                step.id.bright_blue(),
+                result.code_id.as_deref().unwrap_or("-"),
+                status
+            );
+            if result.status == VerifyStatus::Mismatch {
+                println!(
+                    "     on-chain: {}\n     local:    {}\n     optimizer: {}",
+                    result.onchain_checksum.as_deref().unwrap_or("-"),
+                    result.local_checksum.as_deref().unwrap_or("-"),
+                    result.optimizer_checksum.as_deref().unwrap_or("-")
+                );
+            }
+            report.contracts.push(result);
+        }
+        Ok(report)
+    }
+
+    fn check_mismatches(reports: &[VerifyReport]) -> Result<(), WarpError> {
+        let mismatches = reports
+            .iter()
+            .flat_map(|x| x.contracts.iter())
+            .filter(|x| x.status == VerifyStatus::Mismatch)
+            .count();
+        if mismatches > 0 {
+            return Err(WarpError::VerificationFailed(mismatches));
+        }
+        Ok(())
+    }
+
+    /// The config of a deployment key: an environment from Warp.toml or a chain ID, using the
+    /// network recorded in Deployment.toml. The networks are queried with the active profile.
+    fn network_config(
+        key: &str,
+        config: &ProjectConfig,
+        deployments: &DeploymentResult,
+    ) -> Option<ProjectConfig> {
+        let mut config = config.clone();
+        if config.environments.contains_key(key) {
+            config.apply_environment(key).ok()?;
+            return Some(config);
+        }
+        if config.network.chain_id != key {
+            let network = deployments.networks.get(key)?;
+            config.network.chain_id = network.chain_id.clone();
+            config.network.rpc_url = network.rpc_url.clone();
+            config.network.denom = network.denom.clone();
+        }
+        config.active_environment = None;
+        Some(config)
+    }
+
+    fn verify_step(
+        step: &AutoDeployStep,
+        project_root: &Path,
+        config: &ProjectConfig,
+        deployments: &DeploymentResult,
+        profile: &dyn ChainProfile,
+    ) -> Result<ContractVerification, WarpError> {
+        let key = config.deployment_key();
+        let address = deployments
+            .deployment
+            .get(&key)
+            .and_then(|x| x.get(&step.id))
+            .cloned();
+        // The code a contract runs is taken from the chain, store-only steps use the recorded code
+        let code_id = match &address {
+            Some(address) => Some(profile.query_contract_info(address, config)?.contract_info.code_id),
+            None => deployments
+                .records
+                .get(&key)
+                .and_then(|x| x.get(&step.id))
+                .and_then(|x| x.code_id.clone()),
+        };
+        let onchain_checksum = match &code_id {
+            Some(code_id) => Some(profile.query_code_info(code_id, config)?.checksum.to_lowercase()),
+            None => None,
+        };
+        let artifact = project_root.join(&step.contract);
+        let local_checksum = if artifact.exists() {
+            Some(hex::encode(file_util::sha256_file(&artifact)?))
+        } else {
+            None
+        };
+        let optimizer_checksum = Self::optimizer_checksum(&artifact)?;
+
+        let status = match (&onchain_checksum, &local_checksum) {
+            (None, _) => VerifyStatus::NotDeployed,
+            (Some(_), None) => VerifyStatus::MissingArtifact,
+            (Some(onchain), Some(local)) => {
+                if onchain == local && optimizer_checksum.as_ref().is_none_or(|x| x == onchain) {
+                    VerifyStatus::Match
+                } else {
+                    VerifyStatus::Mismatch
+                }
+            }
+        };
+        Ok(ContractVerification {
+            step: step.id.clone(),
+            artifact: step.contract.clone(),
+            address,
+            code_id,
+            onchain_checksum,
+            local_checksum,
+            optimizer_checksum,
+            status,
+        })
+    }
+
+    /// Looks up the artifact in the `checksums.txt` file written next to it by the optimizer
+    fn optimizer_checksum(artifact: &Path) -> Result<Option<String>, WarpError> {
+        let checksums = artifact.with_file_name("checksums.txt");
+        if !checksums.exists() {
+            return Ok(None);
+        }
+        let file_name = artifact
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Ok(std::fs::read_to_string(checksums)?
+            .lines()
+            .filter_map(|x| x.split_once(char::is_whitespace))
+            .find(|(_, name)| name.trim() == file_name)
+            .map(|(checksum, _)| checksum.to_lowercase()))
+    }
+}
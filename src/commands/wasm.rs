@@ -1,13 +1,22 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::Subcommand;
 pub use clap::{arg, Args};
+use owo_colors::OwoColorize;
 
 use crate::{
     chains::chain_profile::ChainProfile,
+    cosmos::address::instantiate2_address,
     error::WarpError,
     executable::Executable,
-    utils::{deployment_result::DeploymentResult, project_config::ProjectConfig},
+    utils::{
+        credentials,
+        deployment_result::DeploymentResult,
+        file_util,
+        gas::{self, retry_out_of_gas},
+        offline_tx::{PendingTx, PendingTxs, TxKind},
+        project_config::{ProjectConfig, DEFAULT_GAS_ADJUSTMENT},
+    },
 };
 
 #[derive(Args)]
@@ -15,6 +24,9 @@ pub struct WasmCommand {
     /// Wasm Subcommand
     #[command(subcommand)]
     subcommand: WasmSubcommand,
+    /// Don't ask for a keyring password (see also WARP_KEYRING_PASSWORD)
+    #[arg(long, global = true)]
+    no_password: bool,
 }
 
 #[derive(Subcommand)]
@@ -23,6 +35,56 @@ pub enum WasmSubcommand {
     Execute(WasmExecuteArgs),
     ///Query a contract in the workspace
     Query(WasmQueryArgs),
+    /// Migrate a contract in the workspace to a new code ID
+    Migrate(WasmMigrateArgs),
+    /// Estimate the gas and fee of executing a contract in the workspace
+    Simulate(WasmSimulateArgs),
+    /// Compute the address of a step instantiated with a salt (instantiate2)
+    PredictAddress(WasmPredictAddressArgs),
+    /// Manage the admin of a contract in the workspace
+    Admin {
+        #[command(subcommand)]
+        command: WasmAdminSubcommand,
+    },
+}
+
+#[derive(Args, Clone)]
+pub struct WasmPredictAddressArgs {
+    /// Step ID (from the Warp.toml file)
+    #[arg(required = true)]
+    pub step: String,
+    /// Address of the instantiating account (defaults to the deployment account)
+    #[arg(long)]
+    pub creator: Option<String>,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum WasmAdminSubcommand {
+    /// Transfer the admin rights of a contract to a new address
+    Set(WasmAdminSetArgs),
+    /// Remove the admin of a contract, making it immutable
+    Clear(WasmAdminClearArgs),
+}
+
+#[derive(Args, Clone)]
+pub struct WasmAdminSetArgs {
+    /// Contract ID (from the Warp.toml file)
+    #[arg(required = true)]
+    pub contract: String,
+    /// The new admin - an address or another contract ID (from the Warp.toml file)
+    #[arg(required = true)]
+    pub new_admin: String,
+    #[arg(long, short)]
+    pub from: Option<String>,
+}
+
+#[derive(Args, Clone)]
+pub struct WasmAdminClearArgs {
+    /// Contract ID (from the Warp.toml file)
+    #[arg(required = true)]
+    pub contract: String,
+    #[arg(long, short)]
+    pub from: Option<String>,
 }
 
 #[derive(Args, Clone)]
@@ -39,6 +101,39 @@ pub struct WasmExecuteArgs {
     pub funds: Option<String>, // TODO: Implement
     #[arg(long, short)]
     pub yes: Option<bool>, // TODO: Implement
+    /// Write the unsigned transaction to .warp/txs/ instead of broadcasting it
+    #[arg(long)]
+    pub generate_only: bool,
+}
+
+#[derive(Args, Clone)]
+pub struct WasmMigrateArgs {
+    /// Contract ID (from the Warp.toml file)
+    #[arg(required = true)]
+    pub contract: String,
+    /// The new code ID
+    #[arg(required = true)]
+    pub code_id: String,
+    /// JSON-serialized migrate message
+    #[arg(default_value = "{}")]
+    pub migrate_msg: String,
+    #[arg(long, short)]
+    pub from: Option<String>,
+    /// Write the unsigned transaction to .warp/txs/ instead of broadcasting it
+    #[arg(long)]
+    pub generate_only: bool,
+}
+
+#[derive(Args, Clone)]
+pub struct WasmSimulateArgs {
+    /// Contract ID (from the Warp.toml file)
+    #[arg(required = true)]
+    pub contract: String,
+    /// JSON-serialized contract arguments
+    #[arg(required = true)]
+    pub arguments: String,
+    #[arg(long, short)]
+    pub from: Option<String>,
 }
 
 #[derive(Args, Clone)]
@@ -62,22 +157,36 @@ impl Executable for WasmCommand {
         if project_root.is_none() {
             return Err(WarpError::ProjectFileNotFound);
         };
-        //let project_root = project_root.unwrap();
+        let project_root = project_root.unwrap();
         let config = config.unwrap();
+        if let WasmSubcommand::PredictAddress(x) = &self.subcommand {
+            return Self::predict_address(x, &project_root, &config, profile.as_ref());
+        }
         let (_, mut deployments) = DeploymentResult::parse()?;
 
         // Translate contract address
         let contract_id = match &self.subcommand {
             WasmSubcommand::Execute(x) => &x.contract,
             WasmSubcommand::Query(x) => &x.contract,
+            WasmSubcommand::Migrate(x) => &x.contract,
+            WasmSubcommand::Simulate(x) => &x.contract,
+            WasmSubcommand::Admin { command } => match command {
+                WasmAdminSubcommand::Set(x) => &x.contract,
+                WasmAdminSubcommand::Clear(x) => &x.contract,
+            },
+            WasmSubcommand::PredictAddress(_) => unreachable!(),
         };
-        let contract_address = deployments
-            .network(&config.network.chain_id)
-            .get(contract_id);
+        let deployed_contracts = deployments.network(&config.deployment_key());
+        let contract_address = deployed_contracts.get(contract_id);
         if contract_address.is_none() {
             return Err(WarpError::ContractIdNotFound(contract_id.to_owned()));
         }
         let contract_address = contract_address.unwrap();
+        // Gas settings of the contract's step apply to its transactions
+        let config = match config.autodeploy.steps.iter().find(|x| &x.id == contract_id) {
+            Some(step) => config.for_step(step),
+            None => config,
+        };
 
         match &self.subcommand {
             WasmSubcommand::Execute(x) => {
@@ -87,19 +196,81 @@ impl Executable for WasmCommand {
                 }
 
                 let password =
-                    rpassword::prompt_password("Enter your keyring password (if using/needed):")?;
-                let password = if password.is_empty() {
-                    None
-                } else {
-                    Some(password.as_str())
-                };
-
-                profile.execute_contract(
-                    &contract_address,
+                    credentials::keyring_password(self.no_password, &project_root, &config)?;
+                let password = password.as_deref();
+
+                if x.generate_only {
+                    let path = Self::pending_tx(&project_root, &config, contract_id, TxKind::Execute)?;
+                    profile.execute_contract(
+                        contract_address,
+                        &x.arguments,
+                        from,
+                        password,
+                        &config.with_generate_only(path.clone()),
+                    )?;
+                    println!("{} {}", "Generated:".bright_green(), path.display());
+                    return Ok(());
+                }
+
+                retry_out_of_gas(&config, |config| {
+                    profile.execute_contract(contract_address, &x.arguments, from, password, config)
+                })?;
+            }
+            WasmSubcommand::Migrate(x) => {
+                let from = x.from.as_ref().unwrap_or(&config.autodeploy.account_id);
+                if from.is_empty() {
+                    return Err(WarpError::UnspecifiedWallet);
+                }
+
+                let password =
+                    credentials::keyring_password(self.no_password, &project_root, &config)?;
+                let password = password.as_deref();
+
+                if x.generate_only {
+                    let path = Self::pending_tx(&project_root, &config, contract_id, TxKind::Migrate)?;
+                    profile.migrate_contract(
+                        contract_address,
+                        &x.code_id,
+                        from,
+                        &x.migrate_msg,
+                        password,
+                        &config.with_generate_only(path.clone()),
+                    )?;
+                    println!("{} {}", "Generated:".bright_green(), path.display());
+                    return Ok(());
+                }
+
+                let response = retry_out_of_gas(&config, |config| {
+                    profile.migrate_contract(
+                        contract_address,
+                        &x.code_id,
+                        from,
+                        &x.migrate_msg,
+                        password,
+                        config,
+                    )
+                })?;
+                println!(
+                    "{} ({})",
+                    "Done.".bright_green(),
+                    response.txhash.bright_blue()
+                );
+            }
+            WasmSubcommand::Simulate(x) => {
+                let from = x.from.as_ref().unwrap_or(&config.autodeploy.account_id);
+                if from.is_empty() {
+                    return Err(WarpError::UnspecifiedWallet);
+                }
+
+                let password =
+                    credentials::keyring_password(self.no_password, &project_root, &config)?;
+                Self::simulate(
+                    contract_address,
                     &x.arguments,
                     from,
-                    password,
+                    password.as_deref(),
                     &config,
+                    profile.as_ref(),
                 )?;
             }
             WasmSubcommand::Query(x) => {
@@ -107,7 +278,132 @@ impl Executable for WasmCommand {
                     profile.query_contract_smart(contract_address, &x.arguments, &config)?;
                 println!("{result}");
             }
+            WasmSubcommand::Admin { command } => {
+                let from = match command {
+                    WasmAdminSubcommand::Set(x) => x.from.as_ref(),
+                    WasmAdminSubcommand::Clear(x) => x.from.as_ref(),
+                }
+                .unwrap_or(&config.autodeploy.account_id);
+                if from.is_empty() {
+                    return Err(WarpError::UnspecifiedWallet);
+                }
+
+                let password =
+                    credentials::keyring_password(self.no_password, &project_root, &config)?;
+                let password = password.as_deref();
+
+                let response = retry_out_of_gas(&config, |config| match command {
+                    WasmAdminSubcommand::Set(x) => {
+                        let new_admin = deployed_contracts
+                            .get(&x.new_admin)
+                            .unwrap_or(&x.new_admin);
+                        profile.update_admin(contract_address, new_admin, from, password, config)
+                    }
+                    WasmAdminSubcommand::Clear(_) => {
+                        profile.clear_admin(contract_address, from, password, config)
+                    }
+                })?;
+                println!(
+                    "{} ({})",
+                    "Done.".bright_green(),
+                    response.txhash.bright_blue()
+                );
+            }
+            WasmSubcommand::PredictAddress(_) => unreachable!(),
+        }
+        Ok(())
+    }
+}
+
+impl WasmCommand {
+    /// Registers a transaction generated with `--generate-only` and returns its file
+    fn pending_tx(
+        project_root: &Path,
+        config: &ProjectConfig,
+        contract_id: &str,
+        kind: TxKind,
+    ) -> Result<PathBuf, WarpError> {
+        let mut pending = PendingTxs::load(project_root)?;
+        let path = pending.add(
+            project_root,
+            PendingTx {
+                deployment: config.deployment_key(),
+                step: contract_id.to_owned(),
+                kind,
+                checksum: None,
+            },
+        )?;
+        pending.save(project_root)?;
+        Ok(path)
+    }
+
+    fn simulate(
+        contract_address: &str,
+        msg: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+        profile: &dyn ChainProfile,
+    ) -> Result<(), WarpError> {
+        let gas_used = profile.simulate_execute(contract_address, msg, from, password, config)?;
+        let settings = config.network.gas.clone().unwrap_or_default();
+        let adjustment = settings.adjustment.unwrap_or(DEFAULT_GAS_ADJUSTMENT);
+        let gas_limit = settings
+            .limit
+            .unwrap_or((gas_used as f64 * adjustment).ceil() as u64);
+        println!(" {} Estimated gas: {}", "=>".bright_yellow(), gas_used.bright_green());
+        match settings.limit {
+            Some(_) => println!(" {} Gas limit (fixed): {}", "=>".bright_yellow(), gas_limit),
+            None => println!(
+                " {} Gas limit (adjustment {}): {}",
+                "=>".bright_yellow(),
+                adjustment,
+                gas_limit
+            ),
+        }
+        let gas_price = match config.network.gas_prices.as_deref() {
+            Some(x) if x != "auto" => Some(x.to_owned()),
+            _ => profile.query_gas_price(config)?,
+        };
+        match gas_price.and_then(|x| gas::estimate_fee(gas_limit, &x)) {
+            Some(fee) => println!(" {} Fee: {}", "=>".bright_yellow(), fee.bright_green()),
+            None => println!(" {} Fee: unknown (no gas price)", "=>".bright_yellow()),
         }
         Ok(())
     }
+
+    fn predict_address(
+        args: &WasmPredictAddressArgs,
+        project_root: &Path,
+        config: &ProjectConfig,
+        profile: &dyn ChainProfile,
+    ) -> Result<(), WarpError> {
+        let step = config
+            .autodeploy
+            .steps
+            .iter()
+            .find(|x| x.id == args.step)
+            .ok_or_else(|| WarpError::ContractIdNotFound(args.step.clone()))?;
+        let salt = step
+            .salt
+            .as_ref()
+            .ok_or_else(|| WarpError::MissingSalt(step.id.clone()))?;
+        let creator = match &args.creator {
+            Some(x) => x.clone(),
+            None => {
+                profile
+                    .get_key_info(&config.autodeploy.account_id, None, config)?
+                    .address
+            }
+        };
+        let checksum = file_util::sha256_file(project_root.join(&step.contract))?;
+        let address = instantiate2_address(&checksum, &creator, salt.as_bytes())?;
+        println!(
+            " {} {}: {}",
+            "=>".bright_yellow(),
+            step.id.bright_blue(),
+            address.bright_cyan()
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,88 @@
+use bech32::{Bech32, Hrp};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use crate::error::WarpError;
+
+/// Computes the address of a contract instantiated with `instantiate2`, the same way `wasmd` does
+/// (see `cosmwasm_std::instantiate2_address`).
+pub fn instantiate2_address(
+    checksum: &[u8],
+    creator: &str,
+    salt: &[u8],
+) -> Result<String, WarpError> {
+    if salt.is_empty() || salt.len() > 64 {
+        return Err(WarpError::InvalidSalt(salt.len()));
+    }
+    let (hrp, creator_data) = decode(creator)?;
+
+    let mut key = Vec::<u8>::new();
+    key.extend_from_slice(b"wasm\0");
+    key.extend_from_slice(&(checksum.len() as u64).to_be_bytes());
+    key.extend_from_slice(checksum);
+    key.extend_from_slice(&(creator_data.len() as u64).to_be_bytes());
+    key.extend_from_slice(&creator_data);
+    key.extend_from_slice(&(salt.len() as u64).to_be_bytes());
+    key.extend_from_slice(salt);
+    // No fixed init message
+    key.extend_from_slice(&0u64.to_be_bytes());
+
+    encode(hrp, &module_hash("module", &key))
+}
+
+/// Address of a module account (e.g. `gov`), using the prefix of `address`
+pub fn module_address(module: &str, address: &str) -> Result<String, WarpError> {
+    let (hrp, _) = decode(address)?;
+    encode(hrp, &Sha256::digest(module.as_bytes())[..20])
+}
+
+/// Address of an account with a secp256k1 public key (compressed, 33 bytes)
+pub fn account_address(prefix: &str, pubkey: &[u8]) -> Result<String, WarpError> {
+    let hrp = Hrp::parse(prefix)
+        .map_err(|x| WarpError::InvalidAddress(prefix.to_owned(), x.to_string()))?;
+    encode(hrp, &Ripemd160::digest(Sha256::digest(pubkey)))
+}
+
+/// The "Basic Address" hash from ADR-028
+fn module_hash(ty: &str, key: &[u8]) -> Vec<u8> {
+    let inner = Sha256::digest(ty.as_bytes());
+    Sha256::new().chain_update(inner).chain_update(key).finalize().to_vec()
+}
+
+fn decode(address: &str) -> Result<(Hrp, Vec<u8>), WarpError> {
+    bech32::decode(address)
+        .map_err(|x| WarpError::InvalidAddress(address.to_owned(), x.to_string()))
+}
+
+fn encode(hrp: Hrp, data: &[u8]) -> Result<String, WarpError> {
+    bech32::encode::<Bech32>(hrp, data)
+        .map_err(|x| WarpError::InvalidAddress(hrp.to_string(), x.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn module_address_matches_gov_account() {
+        // Any address works, only its prefix is used
+        let address =
+            super::module_address("gov", "cosmos1fl48vsnmsdzcv85q5d2q4z5ajdha8yu34mf0eh").unwrap();
+        assert_eq!(address, "cosmos10d07y265gmmuvt4z0w9aw880jnsr700j6zn9kn");
+    }
+
+    #[test]
+    fn instantiate2_address_matches_cosmjs_vectors() {
+        let checksum =
+            hex::decode("13a1fc994cc6d1c81b746ee0c0ff6f90043875e0bf1d9be6b7d779fc978dc2a5")
+                .unwrap();
+        let address = super::instantiate2_address(
+            &checksum,
+            "purple1nxvenxve42424242hwamhwamenxvenxvhxf2py",
+            b"a",
+        )
+        .unwrap();
+        assert_eq!(
+            address,
+            "purple1t6r960j945lfv8mhl4mage2rg97w63xeynwrupum2s2l7em4lprs9ce5hk"
+        );
+    }
+}
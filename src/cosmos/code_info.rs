@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeInfoResponse {
+    pub code_id: String,
+    pub creator: String,
+    #[serde(alias = "data_hash", alias = "code_hash")]
+    pub checksum: String,
+    pub instantiate_permission: Option<AccessConfig>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListCodeResponse {
+    pub code_infos: Vec<CodeInfoResponse>,
+    #[serde(default)]
+    pub pagination: Option<PageResponse>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageResponse {
+    /// Set if there are more results
+    pub next_key: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccessConfig {
+    pub permission: String,
+    #[serde(default)]
+    pub addresses: Vec<String>,
+}
+
+impl std::fmt::Display for AccessConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.addresses.is_empty() {
+            write!(f, "{}", self.permission)
+        } else {
+            write!(f, "{} [{}]", self.permission, self.addresses.join(", "))
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractInfoResponse {
+    #[serde(alias = "contract_address")]
+    pub address: String,
+    pub contract_info: ContractInfo,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractInfo {
+    pub code_id: String,
+    pub creator: String,
+    #[serde(default)]
+    pub admin: String,
+    pub label: String,
+}
@@ -0,0 +1,99 @@
+use super::tx_query::{Event, TxQueryResponse};
+use crate::error::WarpError;
+
+/// Values of the `key` attribute of all `event_type` events in the transaction.
+///
+/// Chains before SDK 0.50 group the events by message in `logs` (and may repeat them in the
+/// top-level `events`), newer chains only have the top-level `events`.
+pub fn attribute_values(tx: &TxQueryResponse, event_type: &str, key: &str) -> Vec<String> {
+    let find = |events: &mut dyn Iterator<Item = &Event>| -> Vec<String> {
+        events
+            .filter(|x| x.type_field == event_type)
+            .flat_map(|x| x.attributes.iter())
+            .filter(|x| x.key == key)
+            .map(|x| x.value.clone())
+            .collect()
+    };
+    let values = find(&mut tx.logs.iter().flat_map(|x| x.events.iter()));
+    if !values.is_empty() {
+        return values;
+    }
+    find(&mut tx.events.iter())
+}
+
+/// The first value of `event_type.key`, or an error naming the missing attribute
+pub fn attribute(tx: &TxQueryResponse, event_type: &str, key: &str) -> Result<String, WarpError> {
+    attribute_values(tx, event_type, key)
+        .into_iter()
+        .next()
+        .ok_or_else(|| WarpError::EventNotFound(format!("{event_type}.{key}"), tx.txhash.clone()))
+}
+
+/// The code ID of a wasmd `MsgStoreCode` transaction (`store_code.code_id`)
+pub fn code_id(tx: &TxQueryResponse) -> Result<String, WarpError> {
+    attribute(tx, "store_code", "code_id")
+}
+
+/// The addresses of all contracts instantiated by the transaction, in order
+pub fn contract_addresses(tx: &TxQueryResponse) -> Vec<String> {
+    attribute_values(tx, "instantiate", "_contract_address")
+}
+
+/// The address of a contract instantiated by a wasmd transaction (`instantiate._contract_address`)
+pub fn contract_address(tx: &TxQueryResponse) -> Result<String, WarpError> {
+    contract_addresses(tx).into_iter().next().ok_or_else(|| {
+        WarpError::EventNotFound("instantiate._contract_address".to_owned(), tx.txhash.clone())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cosmos::tx_query::{Attribute, Log};
+
+    fn event(type_field: &str, attributes: &[(&str, &str)]) -> Event {
+        Event {
+            type_field: type_field.to_owned(),
+            attributes: attributes
+                .iter()
+                .map(|(key, value)| Attribute {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                    index: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn finds_attributes_in_logs() {
+        let tx = TxQueryResponse {
+            logs: vec![Log {
+                events: vec![
+                    event("message", &[("action", "/cosmwasm.wasm.v1.MsgStoreCode")]),
+                    event("store_code", &[("code_checksum", "abcd"), ("code_id", "12")]),
+                ],
+                ..Default::default()
+            }],
+            // Older chains repeat the events here
+            events: vec![event("store_code", &[("code_id", "12")])],
+            ..Default::default()
+        };
+        assert_eq!(code_id(&tx).unwrap(), "12");
+        assert!(contract_address(&tx).is_err());
+    }
+
+    #[test]
+    fn finds_attributes_in_top_level_events() {
+        let tx = TxQueryResponse {
+            events: vec![
+                event("instantiate", &[("_contract_address", "juno1a"), ("code_id", "12")]),
+                event("wasm", &[("_contract_address", "juno1a"), ("action", "init")]),
+                event("instantiate", &[("_contract_address", "juno1b"), ("code_id", "13")]),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(contract_address(&tx).unwrap(), "juno1a");
+        assert_eq!(contract_addresses(&tx), vec!["juno1a", "juno1b"]);
+    }
+}
@@ -1,2 +1,6 @@
+pub mod address;
+pub mod code_info;
+pub mod events;
 pub mod keys_show;
+pub mod proposal;
 pub mod tx_query;
@@ -0,0 +1,53 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+
+use crate::utils::project_config::InstantiatePermission;
+
+/// A contract to be uploaded by a governance proposal
+pub struct StoreCodeUpload {
+    pub wasm: Vec<u8>,
+    pub instantiate_permission: Option<InstantiatePermission>,
+}
+
+/// Builds the JSON file for `<cli> tx gov submit-proposal` with one `MsgStoreCode` (of type
+/// `msg_type`) per upload
+pub fn store_code_proposal(
+    msg_type: &str,
+    authority: &str,
+    uploads: &[StoreCodeUpload],
+    title: &str,
+    summary: &str,
+    deposit: &str,
+) -> Value {
+    let messages: Vec<Value> = uploads
+        .iter()
+        .map(|x| {
+            let mut msg = json!({
+                "@type": msg_type,
+                "sender": authority,
+                "wasm_byte_code": STANDARD.encode(&x.wasm),
+            });
+            if let Some(permission) = &x.instantiate_permission {
+                msg["instantiate_permission"] = permission.access_config();
+            }
+            msg
+        })
+        .collect();
+    json!({
+        "messages": messages,
+        "metadata": "",
+        "deposit": deposit,
+        "title": title,
+        "summary": summary,
+    })
+}
+
+/// Status of a proposal from `<cli> q gov proposal <id>` (handles both the wrapped and flat outputs)
+pub fn proposal_status(response: &Value) -> Option<String> {
+    response
+        .get("proposal")
+        .unwrap_or(response)
+        .get("status")
+        .and_then(|x| x.as_str())
+        .map(|x| x.to_owned())
+}
@@ -40,4 +40,68 @@ pub enum WarpError {
     SchemaGenerationFailed(String, String),
     #[error("Pipeline Load Error: {0}")]
     PipelineLoadError(String),
+    #[error("Environment '{0}' is not defined in Warp.toml")]
+    UnknownEnvironment(String),
+    #[error("Invalid address '{0}': {1}")]
+    InvalidAddress(String, String),
+    #[error("Salt must be between 1 and 64 bytes long (got {0})")]
+    InvalidSalt(usize),
+    #[error("Step '{0}' doesn't have a salt configured")]
+    MissingSalt(String),
+    #[error("{0} is not supported by the '{1}' profile")]
+    UnsupportedFeature(String, String),
+    #[error("Code ID '{0}' can't be found on the chain")]
+    CodeIdNotFound(String),
+    #[error("Label '{0}' is already used by contract {1}")]
+    LabelTaken(String, String),
+    #[error("Git metadata is not available (is the workspace a git repository?)")]
+    GitUnavailable,
+    #[error("The keyring command failed ({0})")]
+    KeysCommandFailed(ExitStatus),
+    #[error("The keyring password command failed: {0}")]
+    PasswordCommandFailed(String),
+    #[error("No code with checksum '{0}' was found on the chain")]
+    CodeNotFound(String),
+    #[error("Proposal {0} did not pass ({1})")]
+    ProposalNotPassed(String, String),
+    #[error("{0} contract(s) don't match the code on the chain")]
+    VerificationFailed(usize),
+    #[error("Transaction {0} was not confirmed within {1} seconds")]
+    TxTimeout(String, u64),
+    #[error("'{0}' not found in the events of transaction {1}")]
+    EventNotFound(String, String),
+    #[error("There is no contract named '{0}' in the workspace")]
+    UnknownContract(String),
+    #[error("Invalid wasm: {0}")]
+    WasmParseError(#[from] wasmparser::BinaryReaderError),
+    #[error("{0} artifact(s) failed the checks")]
+    WasmCheckFailed(usize),
+    #[error("Contracts over their size budget: {0}")]
+    SizeBudgetExceeded(String),
+    #[error("Invalid optimizer configuration: {0}")]
+    InvalidOptimizer(String),
+    #[error("The optimizer ('{0}') failed ({1})")]
+    OptimizerFailed(String, ExitStatus),
+    #[error("Watching the workspace failed: {0}")]
+    WatchError(#[from] notify::Error),
+    #[error("The build failed ({0})")]
+    BuildFailed(ExitStatus),
+    #[error("'{0}' is not a local node, select one with --env (or pass --allow-remote)")]
+    NotLocalNetwork(String),
+    #[error("No contract schemas found in {0}, generate them with `warp schema`")]
+    NoContractSchemas(PathBuf),
+    #[error("Key '{0}' not found in the native keystore")]
+    KeyNotFound(String),
+    #[error("Key '{0}' already exists in the native keystore")]
+    KeyExists(String),
+    #[error("Invalid key: {0}")]
+    InvalidKey(String),
+    #[error("Proposal {0} was still open after {1}s, continue with `warp deploy --await-proposal {0}`")]
+    ProposalTimeout(String, u64),
+    #[error("Proposal {0} has an unknown status: '{1}'")]
+    UnknownProposalStatus(String, String),
+    #[error("{0} not found, run `warp build --optimized` first")]
+    ArtifactNotFound(PathBuf),
+    #[error("The file watcher stopped unexpectedly")]
+    WatcherStopped,
 }
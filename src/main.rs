@@ -13,15 +13,17 @@ use std::path::PathBuf;
 use chains::{archway::ArchwayProfile, chain_profile::ChainProfile};
 use clap::{command, Parser, Subcommand};
 use commands::{
-    autodeploy::AutoDeployCommand, build::BuildCommand, config::ConfigCommand,
-    frontend::FrontendCommand, init::InitCommand, new::NewCommand, node::NodeCommand,
-    test::TestCommand, wasm::WasmCommand,
+    autodeploy::AutoDeployCommand, build::BuildCommand, check::CheckCommand, config::ConfigCommand,
+    dev::DevCommand,
+    frontend::FrontendCommand, init::InitCommand, inspect::InspectCommand, keys::KeysCommand, new::NewCommand,
+    node::NodeCommand, test::TestCommand, tx::TxCommand, verify::VerifyCommand,
+    wasm::WasmCommand,
 };
 use error::WarpError;
 use executable::Executable;
 use owo_colors::OwoColorize;
 
-use crate::{commands::{pipeline::PipelineCommand, schema::SchemaCommand}, toolchains::pipeline::Pipeline};
+use crate::{commands::{codegen::CodegenCommand, pipeline::PipelineCommand, schema::SchemaCommand}, toolchains::pipeline::Pipeline};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -31,6 +33,9 @@ struct Cli {
     command: Commands,
     #[arg(long, global = true)]
     pub pipeline: Option<PathBuf>,
+    /// Select a named environment from Warp.toml
+    #[arg(long, global = true, env = "WARP_ENV")]
+    pub env: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -41,8 +46,16 @@ enum Commands {
     Config(ConfigCommand),
     /// Build the current workspace
     Build(BuildCommand),
+    /// Check the built artifacts for problems the chain would reject them for
+    Check(CheckCommand),
+    /// Show what a built artifact is made of
+    Inspect(InspectCommand),
+    /// Rebuild, regenerate the schema and redeploy to the local node whenever the sources change
+    Dev(DevCommand),
     /// Generate the schema for the current workspace
     Schema(SchemaCommand),
+    /// Generate code from the contract schemas
+    Codegen(CodegenCommand),
     /// Execute the 'Auto Deploy' script for the workspace (see Warp.toml)
     Deploy(AutoDeployCommand),
     /// Initialize the frontend for the current workspace
@@ -55,6 +68,12 @@ enum Commands {
     Test(TestCommand),
     /// Wasm commands for interacting with deployed contracts
     Wasm(WasmCommand),
+    /// Manage the keys of the active profile's keyring
+    Keys(KeysCommand),
+    /// Sign and broadcast transactions generated with `--generate-only`
+    Tx(TxCommand),
+    /// Compare the local artifacts to the code deployed on the chain
+    Verify(VerifyCommand),
     /// Pipeline related commands
     Pipeline {
         #[command(subcommand)]
@@ -66,8 +85,16 @@ enum Commands {
 fn main() -> Result<(), WarpError> {
     let cli = Cli::parse();
 
-    let (project_root, config) = utils::project_config::ProjectConfig::parse_project_config()
-        .map_or((None, None), |x| (Some(x.0), Some(x.1)));
+    let (project_root, mut config) = match utils::project_config::ProjectConfig::parse_project_config() {
+        Ok(x) => (Some(x.0), Some(x.1)),
+        Err(WarpError::ProjectFileNotFound) => (None, None),
+        // e.g. `max_size = 0`, which would otherwise be reported as a missing project file
+        Err(x) => return Err(x),
+    };
+    if let (Some(env), Some(config)) = (&cli.env, config.as_mut()) {
+        config.apply_environment(env)?;
+        println!("{} {}", "> Using environment:".blue(), env.bright_blue());
+    }
     let profile = if let Some(pipeline_path) = &cli.pipeline {
         let pipeline: Pipeline = toml::from_str(&std::fs::read_to_string(pipeline_path)?)?;
         println!("{} {}", "> Using custom pipeline:".blue(), pipeline.clone().name.bright_blue());
@@ -78,11 +105,11 @@ fn main() -> Result<(), WarpError> {
     else if config.is_some() {
         Some(match config.as_ref().unwrap().network.profile.as_str() {
             "archway" => Box::new(ArchwayProfile) as Box<dyn ChainProfile>,
-            "xion" => Box::new(chains::xion::XionProfile) as Box<dyn ChainProfile>,
+            "xion" => Box::new(chains::xion::XionProfile::default()) as Box<dyn ChainProfile>,
             "scrt" => Box::new(chains::secret::SecretNetworkProfile) as Box<dyn ChainProfile>,
             "sei" => Box::new(chains::sei::SeiProfile) as Box<dyn ChainProfile>,
             "injective" => Box::new(chains::injective::InjectiveProfile) as Box<dyn ChainProfile>,
-            "juno" => Box::new(chains::juno::JunoProfile) as Box<dyn ChainProfile>,
+            "juno" => Box::new(chains::juno::JunoProfile::default()) as Box<dyn ChainProfile>,
             _ => panic!("Unknown profile"),
         })
     }  else {
@@ -99,6 +126,17 @@ fn main() -> Result<(), WarpError> {
         }
     }
 
+    // Native keys are copied to the CLI's keyring before the commands that sign with them
+    if let (
+        Commands::Deploy(_) | Commands::Dev(_) | Commands::Wasm(_) | Commands::Tx(_),
+        Some(root),
+        Some(config),
+        Some(profile),
+    ) = (&cli.command, &project_root, &config, &profile)
+    {
+        utils::keystore::sync_cli_keyring(root, config, profile.as_ref())?;
+    }
+
     // You can check for the existence of subcommands, and if found use their
     // matches just as you would the top level cmd
     let result = match &cli.command {
@@ -110,11 +148,18 @@ fn main() -> Result<(), WarpError> {
         ),
         Commands::New(x) => x.execute(project_root, config, &profile.unwrap()),
         Commands::Build(x) => x.execute(project_root, config, &profile.unwrap()),
+        Commands::Check(x) => x.execute(project_root, config, &profile.unwrap()),
+        Commands::Inspect(x) => x.execute(project_root, config, &profile.unwrap()),
+        Commands::Dev(x) => x.execute(project_root, config, &profile.unwrap()),
         Commands::Schema(x) => x.execute(project_root, config, &profile.unwrap()),
+        Commands::Codegen(x) => x.execute(project_root, config, &profile.unwrap()),
         Commands::Test(x) => x.execute(project_root, config, &profile.unwrap()),
         Commands::Node(x) => x.execute(project_root, config, &profile.unwrap()),
         Commands::Config(x) => x.execute(project_root, config, &profile.unwrap()),
         Commands::Wasm(x) => x.execute(project_root, config, &profile.unwrap()),
+        Commands::Keys(x) => x.execute(project_root, config, &profile.unwrap()),
+        Commands::Tx(x) => x.execute(project_root, config, &profile.unwrap()),
+        Commands::Verify(x) => x.execute(project_root, config, &profile.unwrap()),
         Commands::Frontend(x) => x.execute(project_root, config, &profile.unwrap()),
         Commands::Pipeline { command} => command.execute(project_root, config, &profile.unwrap_or(Box::new(ArchwayProfile) as Box<dyn ChainProfile>)),
     };
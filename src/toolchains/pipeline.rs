@@ -17,4 +17,10 @@ pub struct PipelineConfig {
     pub cli_args_tx: String,
     pub cli_args_network: String,
     pub cli_args_store: String,
+    /// Capabilities the chain supports, e.g. `["iterator", "stargate", "cosmwasm_1_4"]` (wasmd's by default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<Vec<String>>,
+    /// Largest contract the chain accepts in bytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_wasm_size: Option<usize>,
 }
\ No newline at end of file
@@ -0,0 +1,173 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{file_util, git::GitInfo, workspace::ContractCrate};
+use crate::error::WarpError;
+
+pub const MANIFEST_FILENAME: &str = "build-manifest.json";
+
+/// Provenance of the optimized artifacts, written to `artifacts/build-manifest.json`
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct BuildManifest {
+    /// Artifact file name (e.g. `my_contract.wasm`) -> build
+    pub contracts: BTreeMap<String, BuildInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct BuildInfo {
+    /// SHA-256 checksum (hex) of the artifact
+    pub sha256: String,
+    pub size: u64,
+    pub crate_version: Option<String>,
+    pub git_commit: Option<String>,
+    /// Whether the workspace had uncommitted changes
+    pub git_dirty: bool,
+    pub rustc_version: Option<String>,
+    pub optimizer: String,
+    /// Docker image of the optimizer, e.g. `cosmwasm/optimizer:0.16.0`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_digest: Option<String>,
+    /// Hash of the sources and toolchain the artifact was built from (see `workspace::input_hash`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inputs: Option<String>,
+    pub built_at: u64,
+}
+
+/// How the artifacts were built, shared by all contracts of a build
+#[derive(Clone, Debug, Default)]
+pub struct BuildEnvironment {
+    pub optimizer: String,
+    pub image: Option<String>,
+    pub rustc_version: Option<String>,
+}
+
+impl BuildManifest {
+    fn path(project_root: &Path) -> PathBuf {
+        project_root.join("artifacts").join(MANIFEST_FILENAME)
+    }
+
+    pub fn load(project_root: &Path) -> Result<Self, WarpError> {
+        let path = Self::path(project_root);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<(), WarpError> {
+        std::fs::write(
+            Self::path(project_root),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+
+    /// Records the artifacts of the built contracts (others keep their previous entries).
+    /// `inputs` are the input hashes by package name.
+    pub fn record(
+        &mut self,
+        project_root: &Path,
+        contracts: &[ContractCrate],
+        environment: &BuildEnvironment,
+        inputs: &HashMap<String, String>,
+    ) -> Result<(), WarpError> {
+        let git = GitInfo::load(project_root);
+        let image_digest = environment.image.as_deref().and_then(image_digest);
+        let built_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        for contract in contracts {
+            let name = format!("{}.wasm", contract.artifact_name());
+            let artifact = project_root.join("artifacts").join(&name);
+            if !artifact.exists() {
+                continue;
+            }
+            self.contracts.insert(
+                name,
+                BuildInfo {
+                    sha256: hex::encode(file_util::sha256_file(&artifact)?),
+                    size: std::fs::metadata(&artifact)?.len(),
+                    crate_version: Some(contract.version.clone()).filter(|x| !x.is_empty()),
+                    git_commit: git.as_ref().map(|x| x.sha.clone()),
+                    git_dirty: git.as_ref().is_some_and(|x| x.dirty),
+                    rustc_version: environment.rustc_version.clone(),
+                    optimizer: environment.optimizer.clone(),
+                    image: environment.image.clone(),
+                    image_digest: image_digest.clone(),
+                    inputs: inputs.get(&contract.name).cloned(),
+                    built_at,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// Whether the artifact of the contract was built from `inputs` and wasn't modified since
+    pub fn is_up_to_date(
+        &self,
+        project_root: &Path,
+        contract: &ContractCrate,
+        inputs: &str,
+    ) -> Result<bool, WarpError> {
+        let name = format!("{}.wasm", contract.artifact_name());
+        let Some(build) = self.contracts.get(&name) else {
+            return Ok(false);
+        };
+        let artifact = project_root.join("artifacts").join(&name);
+        if build.inputs.as_deref() != Some(inputs) || !artifact.exists() {
+            return Ok(false);
+        }
+        Ok(hex::encode(file_util::sha256_file(&artifact)?) == build.sha256)
+    }
+
+    /// The build of an artifact (e.g. `artifacts/my_contract.wasm`), if it is still the one on disk
+    pub fn find(&self, artifact: &str, checksum: &str) -> Option<&BuildInfo> {
+        let name = Path::new(artifact)
+            .file_name()?
+            .to_string_lossy()
+            .to_string();
+        self.contracts.get(&name).filter(|x| x.sha256 == checksum)
+    }
+}
+
+/// `rustc --version` of the host, or of the optimizer image
+pub fn rustc_version(image: Option<&str>) -> Option<String> {
+    let out = match image {
+        Some(image) => Command::new("docker")
+            .args(["run", "--rm", "--entrypoint", "rustc", image, "--version"])
+            .output(),
+        None => Command::new("rustc").arg("--version").output(),
+    }
+    .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    Some(String::from_utf8(out.stdout).ok()?.trim().to_owned())
+}
+
+/// The `sha256:...` digest of a local docker image
+fn image_digest(image: &str) -> Option<String> {
+    let out = Command::new("docker")
+        .args([
+            "image",
+            "inspect",
+            "--format",
+            "{{index .RepoDigests 0}}",
+            image,
+        ])
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    let digest = String::from_utf8(out.stdout).ok()?;
+    Some(digest.trim().rsplit('@').next()?.to_owned()).filter(|x| x.starts_with("sha256:"))
+}
@@ -0,0 +1,455 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::Value;
+
+use super::contract_schema::{
+    camel_case, pascal_case, reference_name, variants, ContractSchema, MessageVariant,
+};
+
+const HEADER: &str = "// Generated by `warp codegen ts`, do not edit.";
+
+/// Names of global TypeScript types that schema titles (e.g. `String`) must not shadow
+const RESERVED_NAMES: [&str; 9] = [
+    "String", "Number", "Boolean", "Array", "Object", "Record", "Date", "Map", "Set",
+];
+
+/// The file name of a contract's module, e.g. `cw20-base.ts`
+pub fn file_name(schema: &ContractSchema) -> String {
+    format!("{}.ts", schema.contract_name)
+}
+
+/// `index.ts`, which exports each contract module as a namespace (`Cw20Base.ExecuteMsg`)
+pub fn render_index(schemas: &[ContractSchema]) -> String {
+    let mut out = format!("{HEADER}\n\n");
+    for schema in schemas {
+        out.push_str(&format!(
+            "export * as {} from \"./{}\";\n",
+            pascal_case(&schema.contract_name),
+            schema.contract_name
+        ));
+    }
+    out
+}
+
+/// Message and response types plus the query and execute clients of a contract
+pub fn render(schema: &ContractSchema) -> String {
+    let mut types: BTreeMap<String, String> = BTreeMap::new();
+    let messages = [
+        ("InstantiateMsg", &schema.instantiate),
+        ("ExecuteMsg", &schema.execute),
+        ("QueryMsg", &schema.query),
+        ("MigrateMsg", &schema.migrate),
+        ("SudoMsg", &schema.sudo),
+    ];
+    for (name, root) in messages {
+        if let Some(root) = root {
+            add_root(&mut types, root, name);
+        }
+    }
+    // Query name -> response type
+    let mut responses: BTreeMap<String, String> = BTreeMap::new();
+    for (query, root) in schema.responses.iter().flatten() {
+        let name = add_root(&mut types, root, &format!("{}Response", pascal_case(query)));
+        responses.insert(query.clone(), name);
+    }
+
+    let contract = pascal_case(&schema.contract_name);
+    let queries = schema.query.as_ref().map(variants).unwrap_or_default();
+    let executes = schema.execute.as_ref().map(variants).unwrap_or_default();
+
+    let mut out = format!(
+        "{HEADER}\n// {} {}\n\n",
+        schema.contract_name, schema.contract_version
+    );
+    let mut cosmwasm_imports = vec![];
+    if !queries.is_empty() {
+        cosmwasm_imports.push("CosmWasmClient");
+    }
+    if !executes.is_empty() {
+        cosmwasm_imports.extend(["ExecuteResult", "SigningCosmWasmClient"]);
+    }
+    if !cosmwasm_imports.is_empty() {
+        out.push_str(&format!(
+            "import {{ {} }} from \"@cosmjs/cosmwasm-stargate\";\n",
+            cosmwasm_imports.join(", ")
+        ));
+    }
+    if !executes.is_empty() {
+        out.push_str("import { Coin, StdFee } from \"@cosmjs/amino\";\n");
+    }
+    if !cosmwasm_imports.is_empty() {
+        out.push('\n');
+    }
+    for declaration in types.values() {
+        out.push_str(declaration);
+        out.push('\n');
+    }
+
+    let mut query_methods = BTreeSet::new();
+    if !queries.is_empty() {
+        out.push_str(&format!(
+            "export class {contract}QueryClient {{\n  constructor(\n    public readonly client: CosmWasmClient,\n    public readonly contractAddress: string,\n  ) {{}}\n"
+        ));
+        for variant in queries.iter() {
+            let method = camel_case(&variant.name);
+            let response = responses
+                .get(&variant.name)
+                .map(|x| x.as_str())
+                .unwrap_or("unknown");
+            let (params, msg) = method_args(variant, "");
+            out.push_str(&format!(
+                "\n{}  async {method}({params}): Promise<{response}> {{\n    return this.client.queryContractSmart(this.contractAddress, {msg});\n  }}\n",
+                doc_comment(variant.description, 2)
+            ));
+            query_methods.insert(method);
+        }
+        out.push_str("}\n");
+    }
+    if !executes.is_empty() {
+        if queries.is_empty() {
+            out.push_str(&format!(
+                "\nexport class {contract}Client {{\n  constructor(\n    public readonly client: SigningCosmWasmClient,\n    public readonly sender: string,\n    public readonly contractAddress: string,\n  ) {{}}\n"
+            ));
+        } else {
+            out.push_str(&format!(
+                "\nexport class {contract}Client extends {contract}QueryClient {{\n  constructor(\n    public readonly client: SigningCosmWasmClient,\n    public readonly sender: string,\n    contractAddress: string,\n  ) {{\n    super(client, contractAddress);\n  }}\n"
+            ));
+        }
+        for variant in executes.iter() {
+            let mut method = camel_case(&variant.name);
+            // Overriding a query method with another signature doesn't compile
+            if query_methods.contains(&method) {
+                method = format!("execute{}", pascal_case(&variant.name));
+            }
+            let (params, msg) = method_args(variant, ", ");
+            out.push_str(&format!(
+                "\n{}  async {method}({params}fee: StdFee | \"auto\" | number = \"auto\", memo?: string, funds?: readonly Coin[]): Promise<ExecuteResult> {{\n    return this.client.execute(this.sender, this.contractAddress, {msg}, fee, memo, funds);\n  }}\n",
+                doc_comment(variant.description, 2)
+            ));
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+/// The parameters of a client method (followed by `separator` if there are any) and the message
+/// it sends
+fn method_args(variant: &MessageVariant, separator: &str) -> (String, String) {
+    let name = serde_json::to_string(&variant.name).unwrap();
+    match variant.args {
+        None => (String::new(), name),
+        Some(args) if is_empty_object(args) => (String::new(), format!("{{ {name}: {{}} }}")),
+        Some(args) => (
+            format!("args: {}{separator}", ts_type(args, 2)),
+            format!("{{ {name}: args }}"),
+        ),
+    }
+}
+
+/// Declares the root schema and its definitions, returns the name of the root type
+fn add_root(types: &mut BTreeMap<String, String>, root: &Value, fallback: &str) -> String {
+    let name = match root.get("title").and_then(|x| x.as_str()) {
+        // Titles of generic types like `Array_of_Addr` or `Nullable_Config` make poor names
+        Some(title)
+            if is_identifier(title) && !title.contains('_') && !RESERVED_NAMES.contains(&title) =>
+        {
+            title
+        }
+        _ => fallback,
+    }
+    .to_owned();
+    types
+        .entry(name.clone())
+        .or_insert_with(|| declare(&name, root));
+    for (definition, schema) in root
+        .get("definitions")
+        .and_then(|x| x.as_object())
+        .into_iter()
+        .flatten()
+    {
+        types
+            .entry(definition.clone())
+            .or_insert_with(|| declare(definition, schema));
+    }
+    name
+}
+
+fn declare(name: &str, schema: &Value) -> String {
+    let doc = doc_comment(schema.get("description").and_then(|x| x.as_str()), 0);
+    if schema
+        .get("properties")
+        .is_some_and(|x| x.as_object().is_some_and(|x| !x.is_empty()))
+    {
+        format!("{doc}export interface {name} {}\n", object_type(schema, 0))
+    } else {
+        format!("{doc}export type {name} = {};\n", ts_type(schema, 0))
+    }
+}
+
+/// The TypeScript type of a JSON schema, nested objects are indented by `indent` spaces
+pub fn ts_type(schema: &Value, indent: usize) -> String {
+    match schema {
+        Value::Bool(true) => return "unknown".to_owned(),
+        Value::Bool(false) => return "never".to_owned(),
+        _ => (),
+    }
+    if let Some(name) = reference_name(schema) {
+        return name.to_owned();
+    }
+    if let Some(value) = schema.get("const") {
+        return value.to_string();
+    }
+    if let Some(all) = schema.get("allOf").and_then(|x| x.as_array()) {
+        return join(all.iter().map(|x| ts_type(x, indent)), " & ");
+    }
+    for key in ["anyOf", "oneOf"] {
+        if let Some(any) = schema.get(key).and_then(|x| x.as_array()) {
+            return join(any.iter().map(|x| ts_type(x, indent)), " | ");
+        }
+    }
+    if let Some(values) = schema.get("enum").and_then(|x| x.as_array()) {
+        return join(values.iter().map(|x| x.to_string()), " | ");
+    }
+    match schema.get("type") {
+        Some(Value::String(x)) => primitive_type(x, schema, indent),
+        Some(Value::Array(types)) => join(
+            types
+                .iter()
+                .filter_map(|x| x.as_str())
+                .map(|x| primitive_type(x, schema, indent)),
+            " | ",
+        ),
+        _ if schema.get("properties").is_some() => object_type(schema, indent),
+        _ => "unknown".to_owned(),
+    }
+}
+
+fn primitive_type(type_name: &str, schema: &Value, indent: usize) -> String {
+    match type_name {
+        "string" => "string".to_owned(),
+        "integer" | "number" => "number".to_owned(),
+        "boolean" => "boolean".to_owned(),
+        "null" => "null".to_owned(),
+        "object" => object_type(schema, indent),
+        "array" => match schema.get("items") {
+            // Tuples
+            Some(Value::Array(items)) => format!(
+                "[{}]",
+                items
+                    .iter()
+                    .map(|x| ts_type(x, indent))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Some(items) => {
+                let item = ts_type(items, indent);
+                if is_compound(&item) {
+                    format!("({item})[]")
+                } else {
+                    format!("{item}[]")
+                }
+            }
+            None => "unknown[]".to_owned(),
+        },
+        _ => "unknown".to_owned(),
+    }
+}
+
+fn object_type(schema: &Value, indent: usize) -> String {
+    let properties = match schema.get("properties").and_then(|x| x.as_object()) {
+        Some(x) if !x.is_empty() => x,
+        _ => {
+            return match schema.get("additionalProperties") {
+                Some(x @ Value::Object(_)) => format!("Record<string, {}>", ts_type(x, indent)),
+                _ => "Record<string, never>".to_owned(),
+            }
+        }
+    };
+    let required: BTreeSet<&str> = schema
+        .get("required")
+        .and_then(|x| x.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|x| x.as_str())
+        .collect();
+    let pad = " ".repeat(indent);
+    let mut out = "{\n".to_owned();
+    for (key, property) in properties {
+        out.push_str(&doc_comment(
+            property.get("description").and_then(|x| x.as_str()),
+            indent + 2,
+        ));
+        let key_name = if is_identifier(key) {
+            key.clone()
+        } else {
+            serde_json::to_string(key).unwrap()
+        };
+        let optional = if required.contains(key.as_str()) {
+            ""
+        } else {
+            "?"
+        };
+        out.push_str(&format!(
+            "{pad}  {key_name}{optional}: {};\n",
+            ts_type(property, indent + 2)
+        ));
+    }
+    out.push_str(&pad);
+    out.push('}');
+    out
+}
+
+/// Whether the type is a union or intersection, which needs parentheses in `(A | B)[]`
+fn is_compound(ts_type: &str) -> bool {
+    let mut depth = 0;
+    for c in ts_type.chars() {
+        match c {
+            '{' | '[' | '(' | '<' => depth += 1,
+            '}' | ']' | ')' | '>' => depth -= 1,
+            '|' | '&' if depth == 0 => return true,
+            _ => (),
+        }
+    }
+    false
+}
+
+fn is_empty_object(schema: &Value) -> bool {
+    reference_name(schema).is_none()
+        && schema.get("type").and_then(|x| x.as_str()) == Some("object")
+        && schema
+            .get("properties")
+            .and_then(|x| x.as_object())
+            .is_none_or(|x| x.is_empty())
+        && !schema
+            .get("additionalProperties")
+            .is_some_and(|x| x.is_object())
+}
+
+fn doc_comment(description: Option<&str>, indent: usize) -> String {
+    let Some(description) = description.map(|x| x.trim()).filter(|x| !x.is_empty()) else {
+        return String::new();
+    };
+    let pad = " ".repeat(indent);
+    let description = description.replace("*/", "*\\/");
+    let lines: Vec<&str> = description.lines().collect();
+    if lines.len() == 1 {
+        return format!("{pad}/** {} */\n", lines[0]);
+    }
+    let mut out = format!("{pad}/**\n");
+    for line in lines {
+        out.push_str(format!("{pad} * {line}").trim_end());
+        out.push('\n');
+    }
+    out.push_str(&format!("{pad} */\n"));
+    out
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|x| x.is_ascii_alphabetic() || x == '_' || x == '$')
+        && chars.all(|x| x.is_ascii_alphanumeric() || x == '_' || x == '$')
+}
+
+/// Joins the types, without duplicates
+fn join(types: impl Iterator<Item = String>, separator: &str) -> String {
+    let mut unique: Vec<String> = vec![];
+    for x in types {
+        if !unique.contains(&x) {
+            unique.push(x);
+        }
+    }
+    unique.join(separator)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn schema() -> ContractSchema {
+        ContractSchema {
+            contract_name: "cw20-base".to_owned(),
+            contract_version: "1.0.0".to_owned(),
+            execute: Some(json!({
+                "title": "ExecuteMsg",
+                "oneOf": [{
+                    "description": "Moves tokens",
+                    "type": "object",
+                    "required": ["transfer"],
+                    "properties": { "transfer": {
+                        "type": "object",
+                        "required": ["amount", "recipient"],
+                        "properties": {
+                            "amount": { "$ref": "#/definitions/Uint128" },
+                            "recipient": { "type": "string" }
+                        }
+                    }}
+                }],
+                "definitions": { "Uint128": { "type": "string" } }
+            })),
+            query: Some(json!({
+                "title": "QueryMsg",
+                "oneOf": [{
+                    "type": "object",
+                    "required": ["token_info"],
+                    "properties": { "token_info": { "type": "object" } }
+                }]
+            })),
+            responses: Some(BTreeMap::from([(
+                "token_info".to_owned(),
+                json!({
+                    "title": "TokenInfoResponse",
+                    "type": "object",
+                    "required": ["decimals"],
+                    "properties": {
+                        "decimals": { "type": "integer" },
+                        "cap": { "anyOf": [{ "$ref": "#/definitions/Uint128" }, { "type": "null" }] }
+                    },
+                    "definitions": { "Uint128": { "type": "string" } }
+                }),
+            )])),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn renders_types_and_clients() {
+        let out = render(&schema());
+        assert!(out.contains("export type Uint128 = string;\n"));
+        assert!(out.contains(
+            "export type ExecuteMsg = {\n  transfer: {\n    amount: Uint128;\n    recipient: string;\n  };\n};\n"
+        ));
+        assert!(out.contains("  cap?: Uint128 | null;\n  decimals: number;\n"));
+        assert!(out.contains(
+            "  async tokenInfo(): Promise<TokenInfoResponse> {\n    return this.client.queryContractSmart(this.contractAddress, { \"token_info\": {} });"
+        ));
+        assert!(out.contains("export class Cw20BaseClient extends Cw20BaseQueryClient {"));
+        assert!(out.contains("  /** Moves tokens */\n  async transfer(args: {"));
+        // The output only depends on the schema
+        assert_eq!(out, render(&schema()));
+    }
+
+    #[test]
+    fn avoids_shadowing_global_types() {
+        let mut types = BTreeMap::new();
+        let name = add_root(
+            &mut types,
+            &json!({ "title": "String", "type": "string" }),
+            "NameResponse",
+        );
+        assert_eq!(name, "NameResponse");
+        let name = add_root(
+            &mut types,
+            &json!({ "title": "Array_of_Addr", "type": "array", "items": { "type": "string" } }),
+            "ListResponse",
+        );
+        assert_eq!(types[&name], "export type ListResponse = string[];\n");
+        let pairs = json!({ "type": "array", "items": { "type": "array", "items": [{ "type": "string" }, { "type": ["integer", "null"] }] } });
+        assert_eq!(ts_type(&pairs, 0), "[string, number | null][]");
+        let nullable = json!({ "type": "array", "items": { "type": ["string", "null"] } });
+        assert_eq!(ts_type(&nullable, 0), "(string | null)[]");
+    }
+}
@@ -0,0 +1,206 @@
+use std::{
+    process::Output,
+    time::{Duration, Instant},
+};
+
+use serde_json::Value;
+
+use super::project_config::ProjectConfig;
+use crate::{cosmos::tx_query::TxQueryResponse, error::WarpError};
+
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+const DEFAULT_POLL_INTERVAL_MS: u64 = 500;
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(8);
+
+/// Waits until `lookup` finds the transaction, polling with exponential backoff. The timeout
+/// bounds the total wait.
+///
+/// When the timeout expires and the chain has produced a block since the first miss, looks the
+/// transaction up one last time, as the node may index it late.
+pub fn wait_for_tx<T>(
+    tx_hash: &str,
+    config: &ProjectConfig,
+    mut lookup: impl FnMut() -> Result<Option<T>, WarpError>,
+    mut block_height: impl FnMut() -> Result<u64, WarpError>,
+) -> Result<T, WarpError> {
+    let settings = config.network.confirmation.clone().unwrap_or_default();
+    let timeout = Duration::from_secs(settings.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS));
+    let mut interval =
+        Duration::from_millis(settings.poll_interval.unwrap_or(DEFAULT_POLL_INTERVAL_MS));
+    let deadline = Instant::now() + timeout;
+    let mut first_miss_height = None;
+    loop {
+        if let Some(tx) = lookup()? {
+            return Ok(tx);
+        }
+        // The height is only needed for the last lookup, a failed status query isn't fatal
+        if first_miss_height.is_none() {
+            first_miss_height = block_height().ok();
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        std::thread::sleep(interval.min(remaining));
+        interval = (interval * 2).min(MAX_POLL_INTERVAL);
+    }
+
+    if first_miss_height.is_some_and(|height| block_height().is_ok_and(|x| x > height)) {
+        if let Some(tx) = lookup()? {
+            return Ok(tx);
+        }
+    }
+    Err(WarpError::TxTimeout(tx_hash.to_owned(), timeout.as_secs()))
+}
+
+/// Parses the output of `<cli> q tx <hash>`, `None` if the transaction isn't indexed yet
+pub fn parse_tx_lookup(output: &Output) -> Result<Option<TxQueryResponse>, WarpError> {
+    if output.status.success() {
+        return Ok(Some(serde_json::from_slice(&output.stdout)?));
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // e.g. `tx (<hash>) not found` or `rpc error: code = NotFound desc = tx not found: <hash>`
+    if stderr.contains("not found") || stderr.contains("NotFound") {
+        return Ok(None);
+    }
+    Err(WarpError::UnderlyingCliError(stderr.to_string()))
+}
+
+/// Parses the latest block height from `<cli> status` (older CLIs print it to stderr)
+pub fn parse_block_height(output: &Output) -> Result<u64, WarpError> {
+    if !output.status.success() {
+        return Err(WarpError::UnderlyingCliError(
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ));
+    }
+    [&output.stdout, &output.stderr]
+        .into_iter()
+        .filter_map(|x| serde_json::from_slice::<Value>(x).ok())
+        .find_map(|x| {
+            x.get("sync_info")
+                .or_else(|| x.get("SyncInfo"))?
+                .get("latest_block_height")?
+                .as_str()?
+                .parse()
+                .ok()
+        })
+        .ok_or_else(|| {
+            WarpError::UnderlyingCliError(String::from_utf8_lossy(&output.stdout).to_string())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, os::unix::process::ExitStatusExt, process::ExitStatus};
+
+    use super::*;
+    use crate::utils::project_config::ConfirmationSettings;
+
+    fn config(timeout: u64) -> ProjectConfig {
+        let mut config = ProjectConfig::empty();
+        config.network.confirmation = Some(ConfirmationSettings {
+            timeout: Some(timeout),
+            poll_interval: Some(1),
+        });
+        config
+    }
+
+    fn output(code: i32, stdout: &str, stderr: &str) -> Output {
+        Output {
+            status: ExitStatus::from_raw(code << 8),
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: stderr.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn polls_until_the_tx_is_found() {
+        let mut lookups = 0;
+        let result = wait_for_tx(
+            "ABC",
+            &config(60),
+            || {
+                lookups += 1;
+                Ok((lookups == 3).then_some(lookups))
+            },
+            || Ok(1),
+        );
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[test]
+    fn block_height_errors_are_ignored() {
+        let mut lookups = 0;
+        let result = wait_for_tx(
+            "ABC",
+            &config(60),
+            || {
+                lookups += 1;
+                Ok((lookups == 3).then_some(lookups))
+            },
+            || Err(WarpError::UnderlyingCliError("connection reset".to_owned())),
+        );
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[test]
+    fn rpc_errors_are_not_retried() {
+        let mut lookups = 0;
+        let result: Result<(), _> = wait_for_tx(
+            "ABC",
+            &config(60),
+            || {
+                lookups += 1;
+                Err(WarpError::UnderlyingCliError("connection refused".to_owned()))
+            },
+            || unreachable!(),
+        );
+        assert!(matches!(result, Err(WarpError::UnderlyingCliError(_))));
+        assert_eq!(lookups, 1);
+    }
+
+    #[test]
+    fn looks_up_again_after_the_next_block() {
+        // The chain produces a block on every status query, the tx shows up after the first one
+        let height = Cell::new(10);
+        let result = wait_for_tx(
+            "ABC",
+            &config(0),
+            || Ok((height.get() > 11).then_some(())),
+            || {
+                height.set(height.get() + 1);
+                Ok(height.get())
+            },
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn the_timeout_bounds_the_wait() {
+        // No new blocks, so there's no last lookup after the timeout
+        let mut lookups = 0;
+        let start = Instant::now();
+        let result: Result<(), _> = wait_for_tx(
+            "ABC",
+            &config(1),
+            || {
+                lookups += 1;
+                Ok(None)
+            },
+            || Ok(10),
+        );
+        assert!(matches!(result, Err(WarpError::TxTimeout(_, 1))));
+        assert!(start.elapsed() < Duration::from_millis(1500));
+        assert!(lookups > 1);
+    }
+
+    #[test]
+    fn distinguishes_missing_txs_from_errors() {
+        let missing = output(1, "", "Error: rpc error: code = NotFound desc = tx not found: ABC");
+        assert!(parse_tx_lookup(&missing).unwrap().is_none());
+        let failed = output(1, "", "Error: post failed: connection refused");
+        assert!(parse_tx_lookup(&failed).is_err());
+        let status = output(0, "", r#"{"SyncInfo":{"latest_block_height":"42"}}"#);
+        assert_eq!(parse_block_height(&status).unwrap(), 42);
+    }
+}
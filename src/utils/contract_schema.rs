@@ -0,0 +1,152 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::WarpError;
+
+/// The API of a contract, as written by `cosmwasm_schema::write_api!` and collected into
+/// `schema/<contract>.json` by `warp schema`
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct ContractSchema {
+    pub contract_name: String,
+    pub contract_version: String,
+    pub instantiate: Option<Value>,
+    pub execute: Option<Value>,
+    pub query: Option<Value>,
+    pub migrate: Option<Value>,
+    pub sudo: Option<Value>,
+    /// Query name -> schema of its response
+    #[serde(default)]
+    pub responses: Option<BTreeMap<String, Value>>,
+}
+
+/// A variant of an enum message, e.g. `{ "transfer": { ... } }` or `"increment"`
+#[derive(Clone, Debug, PartialEq)]
+pub struct MessageVariant<'a> {
+    pub name: String,
+    /// Schema of the variant's fields, `None` for unit variants sent as a plain string
+    pub args: Option<&'a Value>,
+    pub description: Option<&'a str>,
+}
+
+impl ContractSchema {
+    /// Loads the schemas from `schema/`, sorted by contract name
+    pub fn load_all(project_root: &Path) -> Result<Vec<ContractSchema>, WarpError> {
+        let dir = project_root.join("schema");
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut schemas = vec![];
+        for entry in std::fs::read_dir(dir)?.filter_map(|x| x.ok()) {
+            let path = entry.path();
+            if path.extension().is_none_or(|x| x != "json") {
+                continue;
+            }
+            // Other JSON files (e.g. schemas of single messages) aren't contract APIs
+            if let Ok(schema) = serde_json::from_str(&std::fs::read_to_string(&path)?) {
+                schemas.push(schema);
+            }
+        }
+        schemas.sort_by(|a: &ContractSchema, b| a.contract_name.cmp(&b.contract_name));
+        Ok(schemas)
+    }
+}
+
+/// The variants of an enum message (`oneOf` of single-key objects and string enums), empty for
+/// other messages
+pub fn variants(message: &Value) -> Vec<MessageVariant<'_>> {
+    let mut variants = vec![];
+    for variant in message
+        .get("oneOf")
+        .and_then(|x| x.as_array())
+        .into_iter()
+        .flatten()
+    {
+        let description = variant.get("description").and_then(|x| x.as_str());
+        if let Some(names) = variant.get("enum").and_then(|x| x.as_array()) {
+            variants.extend(
+                names
+                    .iter()
+                    .filter_map(|x| x.as_str())
+                    .map(|name| MessageVariant {
+                        name: name.to_owned(),
+                        args: None,
+                        description,
+                    }),
+            );
+            continue;
+        }
+        let Some(properties) = variant.get("properties").and_then(|x| x.as_object()) else {
+            continue;
+        };
+        if properties.len() != 1 {
+            continue;
+        }
+        let (name, args) = properties.iter().next().unwrap();
+        variants.push(MessageVariant {
+            name: name.clone(),
+            args: Some(args),
+            description,
+        });
+    }
+    variants
+}
+
+/// The name a definition is referenced by, e.g. `Uint128` for `#/definitions/Uint128`
+pub fn reference_name(schema: &Value) -> Option<&str> {
+    schema.get("$ref")?.as_str()?.strip_prefix("#/definitions/")
+}
+
+/// `TransferFrom` for `transfer_from`, `Cw20Base` for `cw20-base`
+pub fn pascal_case(name: &str) -> String {
+    name.split(['_', '-', ' '])
+        .filter(|x| !x.is_empty())
+        .map(|x| {
+            let mut chars = x.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// `transferFrom` for `transfer_from`
+pub fn camel_case(name: &str) -> String {
+    let pascal = pascal_case(name);
+    let mut chars = pascal.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn lists_struct_and_unit_variants() {
+        let message = json!({
+            "oneOf": [
+                { "type": "string", "enum": ["increment"] },
+                {
+                    "description": "Moves tokens",
+                    "type": "object",
+                    "required": ["transfer"],
+                    "properties": { "transfer": { "type": "object" } }
+                }
+            ]
+        });
+        let variants = variants(&message);
+        assert_eq!(variants[0].name, "increment");
+        assert!(variants[0].args.is_none());
+        assert_eq!(variants[1].name, "transfer");
+        assert_eq!(variants[1].description, Some("Moves tokens"));
+        assert_eq!(pascal_case("cw20-base"), "Cw20Base");
+        assert_eq!(camel_case("transfer_from"), "transferFrom");
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+
+use owo_colors::OwoColorize;
+
+use super::deployment_result::{DeploymentCosts, DeploymentResult, TxCost};
+use crate::cosmos::tx_query::TxQueryResponse;
+
+/// Deployments kept in the `history` of Deployment.toml, older ones are dropped
+pub const MAX_HISTORY: usize = 20;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CostKind {
+    Store,
+    Instantiate,
+    Migrate,
+}
+
+impl CostKind {
+    fn name(&self) -> &'static str {
+        match self {
+            CostKind::Store => "store",
+            CostKind::Instantiate => "instantiate",
+            CostKind::Migrate => "migrate",
+        }
+    }
+}
+
+impl TxCost {
+    pub fn from_response(response: &TxQueryResponse) -> Self {
+        Self {
+            tx_hash: response.txhash.clone(),
+            gas_wanted: response.gas_wanted.parse().unwrap_or_default(),
+            gas_used: response.gas_used.parse().unwrap_or_default(),
+            fee: response
+                .tx
+                .as_ref()
+                .map(|x| {
+                    x.auth_info
+                        .fee
+                        .amount
+                        .iter()
+                        .map(|x| format!("{}{}", x.amount, x.denom))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Fee amounts per denom
+    fn fee_coins(&self) -> Vec<(String, u128)> {
+        self.fee
+            .split(',')
+            .filter_map(|x| {
+                let split = x.find(|c: char| !c.is_ascii_digit())?;
+                Some((x[split..].to_owned(), x[..split].parse().ok()?))
+            })
+            .collect()
+    }
+}
+
+/// Gas and fees of the transactions of a deployment
+#[derive(Default)]
+pub struct CostReport {
+    entries: Vec<(String, CostKind, TxCost)>,
+}
+
+impl CostReport {
+    pub fn add(&mut self, step: &str, kind: CostKind, response: &TxQueryResponse) {
+        self.entries
+            .push((step.to_owned(), kind, TxCost::from_response(response)));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn gas_wanted(&self) -> u64 {
+        self.entries.iter().map(|(_, _, x)| x.gas_wanted).sum()
+    }
+
+    pub fn gas_used(&self) -> u64 {
+        self.entries.iter().map(|(_, _, x)| x.gas_used).sum()
+    }
+
+    pub fn fees(&self) -> BTreeMap<String, u128> {
+        let mut fees = BTreeMap::new();
+        for (denom, amount) in self.entries.iter().flat_map(|(_, _, x)| x.fee_coins()) {
+            *fees.entry(denom).or_default() += amount;
+        }
+        fees
+    }
+
+    pub fn print(&self) {
+        println!("Deployment costs:");
+        for (step, kind, cost) in self.entries.iter() {
+            println!(
+                " {} {} {}: {} gas ({}), fee: {}",
+                "=>".bright_yellow(),
+                step.bright_blue(),
+                kind.name(),
+                cost.gas_used,
+                efficiency(cost.gas_used, cost.gas_wanted),
+                if cost.fee.is_empty() { "n/a" } else { &cost.fee }
+            );
+        }
+        println!(
+            "Total: {} gas used of {} wanted ({}), fees: {}",
+            self.gas_used().bright_green(),
+            self.gas_wanted(),
+            efficiency(self.gas_used(), self.gas_wanted()),
+            format_fees(&self.fees()).bright_green()
+        );
+    }
+
+    /// Writes the costs into the step records and appends the totals to the history, which is
+    /// capped at `MAX_HISTORY` entries since Deployment.toml is committed
+    pub fn save(&self, deployments: &mut DeploymentResult, key: &str, git_sha: Option<String>) {
+        for (step, kind, cost) in self.entries.iter() {
+            let record = deployments.record(key, step);
+            let field = match kind {
+                CostKind::Store => &mut record.store_cost,
+                CostKind::Instantiate => &mut record.instantiate_cost,
+                CostKind::Migrate => &mut record.migrate_cost,
+            };
+            *field = Some(cost.clone());
+        }
+        deployments.history.push(DeploymentCosts {
+            deployment: key.to_owned(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            git_sha,
+            gas_wanted: self.gas_wanted(),
+            gas_used: self.gas_used(),
+            fees: format_fees(&self.fees()),
+        });
+        let excess = deployments.history.len().saturating_sub(MAX_HISTORY);
+        deployments.history.drain(..excess);
+    }
+}
+
+fn efficiency(used: u64, wanted: u64) -> String {
+    if wanted == 0 {
+        return "n/a".to_owned();
+    }
+    format!("{:.1}%", used as f64 * 100.0 / wanted as f64)
+}
+
+fn format_fees(fees: &BTreeMap<String, u128>) -> String {
+    if fees.is_empty() {
+        return "n/a".to_owned();
+    }
+    fees.iter()
+        .map(|(denom, amount)| format!("{amount}{denom}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cosmos::tx_query::{Amount, Tx};
+
+    fn response(gas_used: &str, fee: &[(&str, &str)]) -> TxQueryResponse {
+        let mut tx = Tx::default();
+        tx.auth_info.fee.amount = fee
+            .iter()
+            .map(|(amount, denom)| Amount {
+                denom: denom.to_string(),
+                amount: amount.to_string(),
+            })
+            .collect();
+        TxQueryResponse {
+            gas_wanted: "100".to_owned(),
+            gas_used: gas_used.to_owned(),
+            tx: Some(tx),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fees_are_summed_per_denom() {
+        let mut report = CostReport::default();
+        report.add("$_acl", CostKind::Store, &response("80", &[("100", "uarch")]));
+        report.add(
+            "$_acl",
+            CostKind::Instantiate,
+            &response("20", &[("50", "uarch"), ("7", "ibc/ABC")]),
+        );
+        assert_eq!(report.gas_used(), 100);
+        assert_eq!(report.gas_wanted(), 200);
+        assert_eq!(format_fees(&report.fees()), "7ibc/ABC,150uarch");
+    }
+
+    #[test]
+    fn history_keeps_the_latest_deployments() {
+        let mut deployments = DeploymentResult::default();
+        let report = CostReport::default();
+        for i in 0..MAX_HISTORY + 5 {
+            report.save(&mut deployments, &i.to_string(), None);
+        }
+        assert_eq!(deployments.history.len(), MAX_HISTORY);
+        assert_eq!(deployments.history[0].deployment, "5");
+    }
+}
@@ -0,0 +1,64 @@
+use std::{path::Path, process::Command};
+
+use crate::{error::WarpError, utils::project_config::ProjectConfig};
+
+/// Environment variable that holds the keyring password (e.g. a CI secret)
+pub const PASSWORD_ENV: &str = "WARP_KEYRING_PASSWORD";
+
+/// Resolves the keyring password, in order of priority:
+///
+/// 1. `--no-password` - no password at all
+/// 2. the `WARP_KEYRING_PASSWORD` environment variable
+/// 3. `keyring.password_file` from Warp.toml
+/// 4. `keyring.password_command` from Warp.toml (e.g. `pass show warp/deployer`)
+/// 5. no password for the `test` and `native` keyring backends, which don't use one
+/// 6. an interactive prompt
+///
+/// The password is only ever passed to the CLI through stdin, never printed.
+pub fn keyring_password(
+    no_password: bool,
+    project_root: &Path,
+    config: &ProjectConfig,
+) -> Result<Option<String>, WarpError> {
+    if no_password {
+        return Ok(None);
+    }
+    if let Ok(password) = std::env::var(PASSWORD_ENV) {
+        return Ok(non_empty(password));
+    }
+    let keyring = config.keyring.clone().unwrap_or_default();
+    if let Some(file) = &keyring.password_file {
+        let password = std::fs::read_to_string(project_root.join(file))?;
+        return Ok(non_empty(trim_newline(password)));
+    }
+    if let Some(command) = &keyring.password_command {
+        let out = Command::new("sh").args(["-c", command]).output()?;
+        if !out.status.success() {
+            // Only stderr is reported, stdout might contain the password
+            return Err(WarpError::PasswordCommandFailed(
+                String::from_utf8_lossy(&out.stderr).trim().to_owned(),
+            ));
+        }
+        return Ok(non_empty(trim_newline(String::from_utf8(out.stdout)?)));
+    }
+    if matches!(keyring.backend.as_deref(), Some("test" | "native")) {
+        return Ok(None);
+    }
+    let password = rpassword::prompt_password("Enter your keyring password (if using/needed):")?;
+    Ok(non_empty(password))
+}
+
+fn trim_newline(mut value: String) -> String {
+    while value.ends_with('\n') || value.ends_with('\r') {
+        value.pop();
+    }
+    value
+}
+
+fn non_empty(value: String) -> Option<String> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
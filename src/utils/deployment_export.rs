@@ -0,0 +1,154 @@
+use std::collections::BTreeSet;
+
+use serde_json::{json, Map, Value};
+
+use super::{
+    deployment_result::{DeploymentNetwork, DeploymentResult},
+    project_config::{ExportFormat, ProjectConfig},
+};
+use crate::error::WarpError;
+
+/// Renders the deployments in the given format
+pub fn render(
+    format: ExportFormat,
+    config: &ProjectConfig,
+    deployments: &DeploymentResult,
+) -> Result<String, WarpError> {
+    let model = model(config, deployments);
+    Ok(match format {
+        ExportFormat::Json => format!("{}\n", serde_json::to_string_pretty(&model)?),
+        ExportFormat::Ts => format!(
+            "// Generated by `warp deploy export`, do not edit.\n\nexport const deployments = {} as const;\n\nexport type DeploymentName = keyof typeof deployments;\n",
+            serde_json::to_string_pretty(&model)?
+        ),
+        ExportFormat::Env => render_env(&model[config.deployment_key()]),
+    })
+}
+
+/// Contract names without the `$_` prefix of step IDs
+pub fn export_name(step_id: &str) -> &str {
+    step_id.trim_start_matches(['$', '_'])
+}
+
+/// `{ <deployment>: { chainId, rpcUrl, denom, contracts: { <name>: { address, codeId } } } }`
+fn model(config: &ProjectConfig, deployments: &DeploymentResult) -> Value {
+    let keys: BTreeSet<&String> = deployments
+        .deployment
+        .keys()
+        .chain(deployments.records.keys())
+        .chain(deployments.networks.keys())
+        .collect();
+    let mut model = Map::new();
+    for key in keys {
+        let network = network(key, config, deployments);
+        let addresses = deployments.deployment.get(key);
+        let records = deployments.records.get(key);
+        let steps: BTreeSet<&String> = addresses
+            .into_iter()
+            .flat_map(|x| x.keys())
+            .chain(records.into_iter().flat_map(|x| x.keys()))
+            .collect();
+        let mut contracts = Map::new();
+        for step in steps {
+            let mut contract = Map::new();
+            if let Some(address) = addresses.and_then(|x| x.get(step)) {
+                contract.insert("address".to_owned(), json!(address));
+            }
+            if let Some(code_id) = records
+                .and_then(|x| x.get(step))
+                .and_then(|x| x.code_id.as_ref())
+            {
+                contract.insert(
+                    "codeId".to_owned(),
+                    code_id.parse::<u64>().map_or(json!(code_id), |x| json!(x)),
+                );
+            }
+            contracts.insert(export_name(step).to_owned(), Value::Object(contract));
+        }
+        model.insert(
+            key.clone(),
+            json!({
+                "chainId": network.chain_id,
+                "rpcUrl": network.rpc_url,
+                "denom": network.denom,
+                "contracts": contracts,
+            }),
+        );
+    }
+    Value::Object(model)
+}
+
+/// The recorded network of a deployment, falling back to the networks in Warp.toml
+fn network(key: &str, config: &ProjectConfig, deployments: &DeploymentResult) -> DeploymentNetwork {
+    if let Some(network) = deployments.networks.get(key) {
+        return network.clone();
+    }
+    if let Some(network) = config.environments.get(key).and_then(|x| x.network.as_ref()) {
+        return network.into();
+    }
+    if config.deployment_key() == key || config.network.chain_id == key {
+        return (&config.network).into();
+    }
+    DeploymentNetwork {
+        chain_id: key.to_owned(),
+        ..Default::default()
+    }
+}
+
+fn render_env(deployment: &Value) -> String {
+    let mut out = "# Generated by `warp deploy export`, do not edit.\n".to_owned();
+    for (name, key) in [("CHAIN_ID", "chainId"), ("RPC_URL", "rpcUrl"), ("DENOM", "denom")] {
+        out.push_str(&format!(
+            "WARP_{}={}\n",
+            name,
+            deployment[key].as_str().unwrap_or_default()
+        ));
+    }
+    if let Some(contracts) = deployment["contracts"].as_object() {
+        for (name, contract) in contracts {
+            let name: String = name
+                .chars()
+                .map(|x| if x.is_ascii_alphanumeric() { x.to_ascii_uppercase() } else { '_' })
+                .collect();
+            if let Some(address) = contract["address"].as_str() {
+                out.push_str(&format!("WARP_{name}_ADDRESS={address}\n"));
+            }
+            if !contract["codeId"].is_null() {
+                let code_id = &contract["codeId"];
+                out.push_str(&format!(
+                    "WARP_{name}_CODE_ID={}\n",
+                    code_id.as_str().map_or(code_id.to_string(), |x| x.to_owned())
+                ));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_export_contains_addresses_and_code_ids() {
+        let mut config = ProjectConfig::empty();
+        config.network.chain_id = "constantine-3".to_owned();
+        config.network.denom = "aconst".to_owned();
+        let mut deployments = DeploymentResult::default();
+        deployments
+            .network("constantine-3")
+            .insert("$_acl".to_owned(), "archway1acl".to_owned());
+        deployments.record("constantine-3", "$_acl").code_id = Some("42".to_owned());
+
+        let env = render(ExportFormat::Env, &config, &deployments).unwrap();
+        assert!(env.contains("WARP_CHAIN_ID=constantine-3\n"));
+        assert!(env.contains("WARP_DENOM=aconst\n"));
+        assert!(env.contains("WARP_ACL_ADDRESS=archway1acl\n"));
+        assert!(env.contains("WARP_ACL_CODE_ID=42\n"));
+
+        let json: Value =
+            serde_json::from_str(&render(ExportFormat::Json, &config, &deployments).unwrap())
+                .unwrap();
+        assert_eq!(json["constantine-3"]["contracts"]["acl"]["codeId"], 42);
+    }
+}
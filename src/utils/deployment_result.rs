@@ -1,16 +1,84 @@
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::PathBuf,
+};
 
 use serde::{Deserialize, Serialize};
 
 use crate::error::WarpError;
 
-use super::project_config::ProjectConfig;
+use super::{
+    build_manifest::BuildInfo,
+    project_config::{Network, ProjectConfig},
+};
 
 pub const CONFIG_FILENAME: &str = "Deployment.toml";
 
 #[derive(Serialize, Deserialize, Clone, Default)]
 pub struct DeploymentResult {
     pub deployment: HashMap<String, HashMap<String, String>>,
+    /// Details of the deployed steps (deployment key -> step ID -> record)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub records: BTreeMap<String, BTreeMap<String, DeploymentRecord>>,
+    /// Network each deployment was made to (deployment key -> network)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub networks: BTreeMap<String, DeploymentNetwork>,
+    /// Cost totals of past deployments
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<DeploymentCosts>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct DeploymentNetwork {
+    pub chain_id: String,
+    pub rpc_url: String,
+    pub denom: String,
+}
+
+impl From<&Network> for DeploymentNetwork {
+    fn from(network: &Network) -> Self {
+        Self {
+            chain_id: network.chain_id.clone(),
+            rpc_url: network.rpc_url.clone(),
+            denom: network.denom.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct DeploymentRecord {
+    /// Code ID of the uploaded contract
+    pub code_id: Option<String>,
+    /// SHA-256 checksum (hex) of the uploaded contract
+    pub checksum: Option<String>,
+    /// Costs of the latest transactions of the step
+    pub store_cost: Option<TxCost>,
+    pub instantiate_cost: Option<TxCost>,
+    pub migrate_cost: Option<TxCost>,
+    /// How the uploaded artifact was built (from `artifacts/build-manifest.json`)
+    pub build: Option<BuildInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct TxCost {
+    pub tx_hash: String,
+    pub gas_wanted: u64,
+    pub gas_used: u64,
+    /// Fee paid, e.g. `1500aconst` (empty if the CLI didn't return the signed tx)
+    pub fee: String,
+}
+
+/// Totals of a single `warp deploy` run, for tracking costs across releases
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct DeploymentCosts {
+    pub deployment: String,
+    pub timestamp: u64,
+    pub git_sha: Option<String>,
+    pub gas_wanted: u64,
+    pub gas_used: u64,
+    /// Fees per denom, e.g. `1500aconst,20uarch`
+    pub fees: String,
 }
 
 impl DeploymentResult {
@@ -41,6 +109,14 @@ impl DeploymentResult {
         self.deployment.entry(id.to_string()).or_default()
     }
 
+    pub fn record(&mut self, id: &str, step: &str) -> &mut DeploymentRecord {
+        self.records
+            .entry(id.to_string())
+            .or_default()
+            .entry(step.to_string())
+            .or_default()
+    }
+
     pub fn save(&self) -> Result<(), WarpError> {
         let toml_path = ProjectConfig::find_project_root()?.join(CONFIG_FILENAME);
         std::fs::write(toml_path, toml::to_string_pretty(self)?)?;
@@ -1,5 +1,7 @@
 use std::path::Path;
 
+use sha2::{Digest, Sha256};
+
 use crate::error::WarpError;
 
 pub fn replace_in_file<P>(path: P, find: &str, replace: &str) -> Result<(), WarpError>
@@ -12,3 +14,12 @@ where
 
     Ok(())
 }
+
+/// Computes the SHA-256 checksum of a file (e.g. the code checksum of a wasm artifact)
+pub fn sha256_file<P>(path: P) -> Result<Vec<u8>, WarpError>
+where
+    P: AsRef<Path>,
+{
+    let content = std::fs::read(&path)?;
+    Ok(Sha256::digest(content).to_vec())
+}
@@ -0,0 +1,136 @@
+use std::{cell::RefCell, collections::HashMap, process::Output};
+
+use owo_colors::OwoColorize;
+use regex::Regex;
+
+use super::project_config::ProjectConfig;
+use crate::error::WarpError;
+
+pub const DEFAULT_RETRIES: u32 = 2;
+/// How much the gas is increased for every retry
+const RETRY_FACTOR: f64 = 1.5;
+
+/// Runs `tx`, retrying with more gas while it fails with out-of-gas
+pub fn retry_out_of_gas<T>(
+    config: &ProjectConfig,
+    mut tx: impl FnMut(&ProjectConfig) -> Result<T, WarpError>,
+) -> Result<T, WarpError> {
+    let retries = config
+        .network
+        .gas
+        .as_ref()
+        .and_then(|x| x.retries)
+        .unwrap_or(DEFAULT_RETRIES);
+    let mut config = config.clone();
+    let mut attempt = 0;
+    loop {
+        match tx(&config) {
+            Err(err) if attempt < retries && is_out_of_gas(&err) => {
+                attempt += 1;
+                println!(
+                    "{} Transaction ran out of gas, retrying with more gas ({}/{})",
+                    "Warning!".bright_yellow(),
+                    attempt,
+                    retries
+                );
+                config = config.with_more_gas(RETRY_FACTOR);
+            }
+            result => return result,
+        }
+    }
+}
+
+fn is_out_of_gas(err: &WarpError) -> bool {
+    match err {
+        WarpError::TxFailed(_, log) | WarpError::UnderlyingCliError(log) => {
+            log.contains("out of gas")
+        }
+        _ => false,
+    }
+}
+
+/// Reads the gas estimate printed by `<cli> tx ... --dry-run`
+pub fn parse_gas_estimate(output: &Output) -> Result<u64, WarpError> {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let re = Regex::new(r"gas estimate: (\d+)").unwrap();
+    re.captures(&stdout)
+        .or_else(|| re.captures(&stderr))
+        .and_then(|x| x[1].parse().ok())
+        .ok_or_else(|| WarpError::UnderlyingCliError(stderr.to_string()))
+}
+
+/// The fee for `gas` at `gas_price` (e.g. `0.025ujuno`), rounded up
+pub fn estimate_fee(gas: u64, gas_price: &str) -> Option<String> {
+    let split = gas_price.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let price: f64 = gas_price[..split].parse().ok()?;
+    Some(format!("{}{}", (gas as f64 * price).ceil() as u128, &gas_price[split..]))
+}
+
+/// Gas prices queried from the chains, by chain ID, so that a command queries them once rather
+/// than for every transaction
+#[derive(Default)]
+pub struct GasPriceCache(RefCell<HashMap<String, String>>);
+
+impl GasPriceCache {
+    /// The configured gas price, or the one from `query` if it's `auto` or not set
+    pub fn resolve(&self, config: &ProjectConfig, query: impl FnOnce() -> String) -> String {
+        if let Some(x) = config.network.gas_prices.as_deref().filter(|x| *x != "auto") {
+            return x.to_owned();
+        }
+        let chain_id = &config.network.chain_id;
+        if let Some(x) = self.0.borrow().get(chain_id) {
+            return x.clone();
+        }
+        let gas_price = query();
+        self.0.borrow_mut().insert(chain_id.clone(), gas_price.clone());
+        gas_price
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_out_of_gas_with_more_gas() {
+        let config = ProjectConfig::empty();
+        let mut adjustments = vec![];
+        let result = retry_out_of_gas(&config, |config| {
+            adjustments.push(config.gas_cli_args(false)[3].clone());
+            Err::<(), _>(WarpError::TxFailed(
+                "ABC".to_owned(),
+                "out of gas in location: WriteFlat; gasWanted: 100, gasUsed: 120".to_owned(),
+            ))
+        });
+        assert!(result.is_err());
+        assert_eq!(adjustments.len(), 1 + DEFAULT_RETRIES as usize);
+        assert_eq!(adjustments[0], "1.4");
+        assert_eq!(adjustments[1], "2.1");
+        assert_eq!(adjustments[2], "3.15");
+    }
+
+    #[test]
+    fn fee_is_rounded_up() {
+        assert_eq!(estimate_fee(100001, "0.025ujuno").as_deref(), Some("2501ujuno"));
+        assert_eq!(estimate_fee(10, "1000000000000aconst").as_deref(), Some("10000000000000aconst"));
+        assert_eq!(estimate_fee(10, "ujuno"), None);
+    }
+
+    #[test]
+    fn gas_prices_are_queried_once() {
+        let mut config = ProjectConfig::empty();
+        let cache = GasPriceCache::default();
+        let mut queries = 0;
+        for _ in 0..3 {
+            let price = cache.resolve(&config, || {
+                queries += 1;
+                "0.075ujuno".to_owned()
+            });
+            assert_eq!(price, "0.075ujuno");
+        }
+        assert_eq!(queries, 1);
+        config.network.gas_prices = Some("0.1ujuno".to_owned());
+        assert_eq!(cache.resolve(&config, || unreachable!()), "0.1ujuno");
+    }
+}
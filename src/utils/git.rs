@@ -0,0 +1,60 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::error::WarpError;
+
+/// Git metadata of the workspace, used for labels and build records
+#[derive(Clone, Debug, Default)]
+pub struct GitInfo {
+    pub sha: String,
+    pub short_sha: String,
+    pub branch: String,
+    /// Whether there are uncommitted changes
+    pub dirty: bool,
+}
+
+impl GitInfo {
+    /// Returns `None` if the directory is not inside a git repository (or git is not installed)
+    pub fn load(dir: &Path) -> Option<Self> {
+        Some(Self {
+            sha: git(dir, &["rev-parse", "HEAD"])?,
+            short_sha: git(dir, &["rev-parse", "--short", "HEAD"])?,
+            branch: git(dir, &["rev-parse", "--abbrev-ref", "HEAD"])?,
+            dirty: !git(dir, &["status", "--porcelain"])?.is_empty(),
+        })
+    }
+}
+
+/// Files that differ from `git_ref` (including uncommitted and untracked files), relative to `dir`
+pub fn changed_files(dir: &Path, git_ref: &str) -> Result<Vec<PathBuf>, WarpError> {
+    let mut files = vec![];
+    for args in [
+        vec!["diff", "--name-only", "--relative", git_ref],
+        vec!["ls-files", "--others", "--exclude-standard"],
+    ] {
+        let out = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .map_err(|_| WarpError::GitUnavailable)?;
+        if !out.status.success() {
+            return Err(WarpError::UnderlyingCliError(String::from_utf8(out.stderr)?));
+        }
+        files.extend(String::from_utf8(out.stdout)?.lines().map(PathBuf::from));
+    }
+    Ok(files)
+}
+
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let out = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    Some(String::from_utf8(out.stdout).ok()?.trim().to_owned())
+}
@@ -0,0 +1,239 @@
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bip32::{DerivationPath, XPrv};
+use bip39::Mnemonic;
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    chains::chain_profile::ChainProfile,
+    cosmos::{address::account_address, keys_show::KeysShowResponse},
+    error::WarpError,
+    utils::{command_util::CommandWithInput, project_config::ProjectConfig},
+};
+
+/// Directory of the native keystore, relative to the workspace root
+pub const DEFAULT_DIR: &str = ".warp/keys";
+/// Test keyring the chain CLI signs with, inside the native keystore
+pub const CLI_KEYRING_DIR: &str = "cli";
+
+/// A key of the native keystore. Like the `test` backend of the chain CLIs, keys are stored
+/// unencrypted, so the keystore is meant for development and CI.
+#[derive(Serialize, Deserialize)]
+struct KeyFile {
+    name: String,
+    mnemonic: String,
+}
+
+/// Keys managed by Warp without the chain CLI, one JSON file per key
+pub struct NativeKeystore {
+    dir: PathBuf,
+}
+
+impl NativeKeystore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Names of all keys, in alphabetical order
+    pub fn list(&self) -> Result<Vec<String>, WarpError> {
+        if !self.dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut names = vec![];
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|x| x == "json") {
+                names.push(path.file_stem().unwrap().to_string_lossy().to_string());
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Creates a key from a random 24 word mnemonic, which is returned
+    pub fn add(&self, name: &str) -> Result<String, WarpError> {
+        let mut entropy = [0u8; 32];
+        OsRng.fill_bytes(&mut entropy);
+        let mnemonic = Mnemonic::from_entropy(&entropy)
+            .map_err(|x| WarpError::InvalidKey(x.to_string()))?
+            .to_string();
+        self.import(name, &mnemonic)?;
+        Ok(mnemonic)
+    }
+
+    pub fn import(&self, name: &str, mnemonic: &str) -> Result<(), WarpError> {
+        let mnemonic = Mnemonic::parse_normalized(mnemonic.trim())
+            .map_err(|x| WarpError::InvalidKey(x.to_string()))?;
+        let path = self.path(name)?;
+        if path.exists() {
+            return Err(WarpError::KeyExists(name.to_owned()));
+        }
+        std::fs::create_dir_all(&self.dir)?;
+        // Keeps the mnemonics (and the CLI's copies) out of git, the keystore is in the workspace
+        let gitignore = self.dir.join(".gitignore");
+        if !gitignore.exists() {
+            std::fs::write(gitignore, "*\n")?;
+        }
+        let key = KeyFile {
+            name: name.to_owned(),
+            mnemonic: mnemonic.to_string(),
+        };
+        std::fs::write(&path, serde_json::to_string_pretty(&key)?)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(())
+    }
+
+    pub fn mnemonic(&self, name: &str) -> Result<String, WarpError> {
+        let path = self.path(name)?;
+        if !path.exists() {
+            return Err(WarpError::KeyNotFound(name.to_owned()));
+        }
+        let key: KeyFile = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        Ok(key.mnemonic)
+    }
+
+    /// The key in the format of `<cli> keys show --output json`
+    pub fn show(
+        &self,
+        name: &str,
+        prefix: &str,
+        coin_type: u32,
+    ) -> Result<KeysShowResponse, WarpError> {
+        let pubkey = derive_pubkey(&self.mnemonic(name)?, coin_type)?;
+        Ok(KeysShowResponse {
+            name: name.to_owned(),
+            type_field: "local".to_owned(),
+            address: account_address(prefix, &pubkey)?,
+            pubkey: json!({
+                "@type": "/cosmos.crypto.secp256k1.PubKey",
+                "key": STANDARD.encode(pubkey),
+            })
+            .to_string(),
+        })
+    }
+
+    /// Deletes the key and the CLI's copy of it
+    pub fn delete(&self, name: &str) -> Result<(), WarpError> {
+        let path = self.path(name)?;
+        if !path.exists() {
+            return Err(WarpError::KeyNotFound(name.to_owned()));
+        }
+        std::fs::remove_file(path)?;
+        let cli_key = self.cli_key_path(name);
+        if cli_key.exists() {
+            std::fs::remove_file(cli_key)?;
+        }
+        Ok(())
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf, WarpError> {
+        // The name becomes a file name, and is passed to the CLI as `--from`
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|x| x.is_ascii_alphanumeric() || x == '-' || x == '_' || x == '.')
+            || name.starts_with('.')
+        {
+            return Err(WarpError::InvalidKey(format!(
+                "'{name}' can't be used as a key name"
+            )));
+        }
+        Ok(self.dir.join(format!("{name}.json")))
+    }
+
+    fn cli_key_path(&self, name: &str) -> PathBuf {
+        self.dir
+            .join(CLI_KEYRING_DIR)
+            .join("keyring-test")
+            .join(format!("{name}.info"))
+    }
+}
+
+/// Imports the native keys the chain CLI doesn't have yet into its test keyring, so that it can
+/// sign with them. Does nothing if the native keystore isn't the configured keyring backend.
+pub fn sync_cli_keyring(
+    project_root: &Path,
+    config: &ProjectConfig,
+    profile: &dyn ChainProfile,
+) -> Result<(), WarpError> {
+    let Some(dir) = config.native_keystore_dir(project_root) else {
+        return Ok(());
+    };
+    let keystore = NativeKeystore::new(dir);
+    for name in keystore.list()? {
+        if keystore.cli_key_path(&name).exists() {
+            continue;
+        }
+        let out = Command::new(profile.get_executable_name())
+            .args(["keys", "add", &name, "--recover"])
+            .args(["--coin-type", &profile.coin_type().to_string()])
+            .args(config.keyring_cli_args())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .call_process_with_input(&format!("{}\n", keystore.mnemonic(&name)?))?;
+        if !out.status.success() {
+            return Err(WarpError::UnderlyingCliError(
+                String::from_utf8_lossy(&out.stderr).trim().to_owned(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The compressed secp256k1 public key at `m/44'/<coin type>'/0'/0/0`, the path of the chain CLIs
+fn derive_pubkey(mnemonic: &str, coin_type: u32) -> Result<[u8; 33], WarpError> {
+    let mnemonic =
+        Mnemonic::parse_normalized(mnemonic).map_err(|x| WarpError::InvalidKey(x.to_string()))?;
+    let path: DerivationPath = format!("m/44'/{coin_type}'/0'/0/0")
+        .parse()
+        .map_err(|x: bip32::Error| WarpError::InvalidKey(x.to_string()))?;
+    let key = XPrv::derive_from_path(mnemonic.to_seed(""), &path)
+        .map_err(|x| WarpError::InvalidKey(x.to_string()))?;
+    Ok(key.public_key().to_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn derives_the_address_of_the_chain_clis() {
+        let pubkey = derive_pubkey(MNEMONIC, 118).unwrap();
+        assert_eq!(
+            account_address("cosmos", &pubkey).unwrap(),
+            "cosmos19rl4cm2hmr8afy4kldpxz3fka4jguq0auqdal4"
+        );
+    }
+
+    #[test]
+    fn keys_round_trip() {
+        let dir = std::env::temp_dir().join(format!("warp-keystore-{}", std::process::id()));
+        let keystore = NativeKeystore::new(dir.clone());
+        keystore.import("deployer", MNEMONIC).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join(".gitignore")).unwrap(), "*\n");
+        assert!(keystore.import("deployer", MNEMONIC).is_err());
+        assert!(keystore.import("../deployer", MNEMONIC).is_err());
+        keystore.add("ci").unwrap();
+        assert_eq!(keystore.list().unwrap(), vec!["ci", "deployer"]);
+        assert_eq!(
+            keystore.show("deployer", "juno", 118).unwrap().address,
+            "juno19rl4cm2hmr8afy4kldpxz3fka4jguq0a2jwxcf"
+        );
+        keystore.delete("ci").unwrap();
+        assert_eq!(keystore.list().unwrap(), vec!["deployer"]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
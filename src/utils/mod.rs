@@ -1,5 +1,22 @@
+pub mod build_manifest;
+pub mod codegen_ts;
 pub mod command_util;
+pub mod confirmation;
+pub mod contract_schema;
+pub mod cost_report;
+pub mod credentials;
+pub mod deployment_export;
 pub mod deployment_result;
 pub mod deployment_task;
 pub mod project_config;
+pub mod schema_docs;
 pub mod file_util;
+pub mod gas;
+pub mod git;
+pub mod keystore;
+pub mod offline_tx;
+pub mod optimizer;
+pub mod wasm_check;
+pub mod wasm_size;
+pub mod watcher;
+pub mod workspace;
@@ -0,0 +1,145 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{cosmos::tx_query::TxQueryResponse, error::WarpError};
+
+/// Directory for unsigned transactions, relative to the workspace root
+pub const TXS_DIR: &str = ".warp/txs";
+pub const PENDING_FILENAME: &str = "pending.toml";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TxKind {
+    Store,
+    Instantiate,
+    Migrate,
+    Execute,
+}
+
+/// A generated transaction that hasn't been broadcast yet
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PendingTx {
+    /// Deployment key (environment or chain ID) the results are imported into
+    pub deployment: String,
+    /// Step or contract ID from Warp.toml
+    pub step: String,
+    pub kind: TxKind,
+    /// Checksum of the uploaded contract (store transactions only)
+    pub checksum: Option<String>,
+}
+
+/// Index of the transactions in `.warp/txs/`, keyed by file name prefix (e.g. `01-store-acl`)
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct PendingTxs {
+    #[serde(default)]
+    pub txs: BTreeMap<String, PendingTx>,
+}
+
+impl PendingTxs {
+    pub fn load(project_root: &Path) -> Result<Self, WarpError> {
+        let path = project_root.join(TXS_DIR).join(PENDING_FILENAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(toml::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<(), WarpError> {
+        let dir = project_root.join(TXS_DIR);
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join(PENDING_FILENAME), toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Registers a new transaction and returns the file it should be written to. A store,
+    /// instantiate or migrate transaction replaces the pending one of the same step, so that
+    /// generating the deployment again doesn't queue it twice.
+    pub fn add(&mut self, project_root: &Path, tx: PendingTx) -> Result<PathBuf, WarpError> {
+        let dir = project_root.join(TXS_DIR);
+        std::fs::create_dir_all(&dir)?;
+        if tx.kind != TxKind::Execute {
+            let existing = self.txs.iter().find(|(_, x)| {
+                x.deployment == tx.deployment && x.step == tx.step && x.kind == tx.kind
+            });
+            if let Some((prefix, _)) = existing {
+                let prefix = prefix.clone();
+                self.txs.insert(prefix.clone(), tx);
+                return Ok(dir.join(format!("{prefix}.json")));
+            }
+        }
+        let name: String = tx
+            .step
+            .chars()
+            .filter(|x| x.is_ascii_alphanumeric() || *x == '-' || *x == '_')
+            .collect();
+        let kind = serde_json::to_value(&tx.kind)?;
+        let mut index = self.txs.len() + 1;
+        let prefix = loop {
+            let prefix = format!(
+                "{:02}-{}-{}",
+                index,
+                kind.as_str().unwrap_or_default(),
+                name.trim_matches('_')
+            );
+            if !self.txs.contains_key(&prefix) {
+                break prefix;
+            }
+            index += 1;
+        };
+        self.txs.insert(prefix.clone(), tx);
+        Ok(dir.join(format!("{prefix}.json")))
+    }
+
+    /// Finds the transaction a (signed) file belongs to, e.g. `01-store-acl.signed.json`
+    pub fn find(&self, file: &Path) -> Option<(String, PendingTx)> {
+        let name = file.file_name()?.to_string_lossy().to_string();
+        let prefix = name.split('.').next()?;
+        self.txs
+            .get(prefix)
+            .map(|x| (prefix.to_owned(), x.clone()))
+    }
+}
+
+/// Writes the output of a `--generate-only` transaction to `path`
+pub fn save_unsigned(path: &Path, json_data: &[u8]) -> Result<TxQueryResponse, WarpError> {
+    // Make sure the CLI produced a transaction and not an error message
+    let tx: Value = serde_json::from_slice(json_data).map_err(|_| {
+        WarpError::UnderlyingCliError(String::from_utf8_lossy(json_data).to_string())
+    })?;
+    std::fs::write(path, serde_json::to_string_pretty(&tx)?)?;
+    Ok(TxQueryResponse::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(step: &str, kind: TxKind) -> PendingTx {
+        PendingTx {
+            deployment: "constantine-3".to_owned(),
+            step: step.to_owned(),
+            kind,
+            checksum: None,
+        }
+    }
+
+    #[test]
+    fn replaces_transactions_of_the_same_step() {
+        let root = std::env::temp_dir().join(format!("warp-pending-{}", std::process::id()));
+        let mut pending = PendingTxs::default();
+        let store = pending.add(&root, tx("acl", TxKind::Store)).unwrap();
+        pending.add(&root, tx("acl", TxKind::Instantiate)).unwrap();
+        assert_eq!(pending.add(&root, tx("acl", TxKind::Store)).unwrap(), store);
+        assert_eq!(pending.txs.len(), 2);
+        // Executing a contract twice is two transactions
+        pending.add(&root, tx("acl", TxKind::Execute)).unwrap();
+        pending.add(&root, tx("acl", TxKind::Execute)).unwrap();
+        assert_eq!(pending.txs.len(), 4);
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
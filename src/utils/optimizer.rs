@@ -0,0 +1,403 @@
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use super::{
+    build_manifest::{self, BuildEnvironment},
+    file_util,
+    project_config::Tooling,
+    workspace::{self, ContractCrate},
+};
+use crate::error::WarpError;
+
+pub const DEFAULT_OPTIMIZER_IMAGE: &str = "cosmwasm/optimizer";
+pub const DEFAULT_OPTIMIZER_VERSION: &str = "0.16.0";
+
+/// Suffixes some optimizers add to the artifact names (`my_contract-aarch64.wasm`)
+const ARCH_SUFFIXES: [&str; 2] = ["-x86_64", "-aarch64"];
+
+/// A way of building the optimized artifacts into `artifacts/`
+pub trait Optimizer {
+    /// Builds the given contracts, or the whole workspace
+    fn build(
+        &self,
+        project_root: &Path,
+        contracts: Option<&[ContractCrate]>,
+    ) -> Result<(), WarpError>;
+    /// Whether `build` can build single contracts instead of the whole workspace
+    fn builds_single_contracts(&self) -> bool {
+        true
+    }
+    /// How the artifacts are built, for the build manifest
+    fn environment(&self) -> BuildEnvironment;
+    /// Identifies the optimizer and its toolchain, artifacts built by another one are rebuilt
+    fn toolchain(&self) -> String;
+}
+
+/// The optimizer selected by `tooling.optimizer_backend`
+pub fn optimizer(tooling: &Tooling) -> Result<Box<dyn Optimizer>, WarpError> {
+    match tooling.optimizer_backend.as_str() {
+        "" | "default" | "docker" => Ok(Box::new(DockerOptimizer {
+            image: docker_image(
+                tooling.optimizer_image.as_deref(),
+                tooling.optimizer_version.as_deref(),
+                std::env::consts::ARCH,
+            ),
+        })),
+        "cw-optimizoor" => Ok(Box::new(CwOptimizoor)),
+        "local" => Ok(Box::new(LocalOptimizer)),
+        "custom" => match &tooling.optimizer_command {
+            Some(command) => Ok(Box::new(CustomOptimizer {
+                command: command.clone(),
+            })),
+            None => Err(WarpError::InvalidOptimizer(
+                "the 'custom' backend needs an `optimizer_command`".to_owned(),
+            )),
+        },
+        x => Err(WarpError::InvalidOptimizer(format!(
+            "unknown backend '{x}' (expected 'default', 'cw-optimizoor', 'local' or 'custom')"
+        ))),
+    }
+}
+
+/// The `cosmwasm/optimizer` docker image (or a compatible one)
+pub struct DockerOptimizer {
+    pub image: String,
+}
+
+impl Optimizer for DockerOptimizer {
+    fn build(
+        &self,
+        project_root: &Path,
+        contracts: Option<&[ContractCrate]>,
+    ) -> Result<(), WarpError> {
+        // Docker volume names can't contain spaces and the like
+        let cache = project_root
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .replace(|x: char| !x.is_ascii_alphanumeric() && x != '-', "_");
+        run(Command::new("docker")
+            .current_dir(project_root)
+            .args(["run", "--rm", "-v"])
+            .arg(format!("{}:/code", project_root.display()))
+            .arg("--mount")
+            .arg(format!(
+                "type=volume,source={cache}_cache,target=/code/target"
+            ))
+            .args([
+                "--mount",
+                "type=volume,source=registry_cache,target=/usr/local/cargo/registry",
+                &self.image,
+            ])
+            // The image builds the contract directories it's given
+            .args(contract_dirs(project_root, contracts)))
+    }
+
+    fn environment(&self) -> BuildEnvironment {
+        BuildEnvironment {
+            optimizer: "docker".to_owned(),
+            image: Some(self.image.clone()),
+            rustc_version: build_manifest::rustc_version(Some(&self.image)),
+        }
+    }
+
+    fn toolchain(&self) -> String {
+        self.image.clone()
+    }
+}
+
+/// `cargo cw-optimizoor`, which doesn't need docker
+pub struct CwOptimizoor;
+
+impl Optimizer for CwOptimizoor {
+    fn build(
+        &self,
+        project_root: &Path,
+        _contracts: Option<&[ContractCrate]>,
+    ) -> Result<(), WarpError> {
+        run(Command::new("cargo")
+            .current_dir(project_root)
+            .args(["cw-optimizoor", "."]))
+    }
+
+    fn builds_single_contracts(&self) -> bool {
+        false
+    }
+
+    fn environment(&self) -> BuildEnvironment {
+        BuildEnvironment {
+            optimizer: "cw-optimizoor".to_owned(),
+            image: None,
+            rustc_version: build_manifest::rustc_version(None),
+        }
+    }
+
+    fn toolchain(&self) -> String {
+        host_toolchain("cw-optimizoor")
+    }
+}
+
+/// `cargo build --release` followed by `wasm-opt`, with the host's toolchain
+pub struct LocalOptimizer;
+
+impl Optimizer for LocalOptimizer {
+    fn build(
+        &self,
+        project_root: &Path,
+        contracts: Option<&[ContractCrate]>,
+    ) -> Result<(), WarpError> {
+        let contracts = match contracts {
+            Some(x) => x.to_vec(),
+            None => workspace::contract_crates(project_root)?,
+        };
+        run(Command::new("cargo")
+            .current_dir(project_root)
+            .args([
+                "build",
+                "--release",
+                "--lib",
+                "--target",
+                "wasm32-unknown-unknown",
+            ])
+            .args(
+                contracts
+                    .iter()
+                    .flat_map(|x| ["-p".to_owned(), x.name.clone()]),
+            )
+            .env("RUSTFLAGS", "-C link-arg=-s"))?;
+
+        let artifacts = project_root.join("artifacts");
+        std::fs::create_dir_all(&artifacts)?;
+        for contract in contracts.iter() {
+            let file = format!("{}.wasm", contract.artifact_name());
+            run(Command::new("wasm-opt")
+                .args(["-Os", "--signext-lowering"])
+                .arg(
+                    project_root
+                        .join("target/wasm32-unknown-unknown/release")
+                        .join(&file),
+                )
+                .arg("-o")
+                .arg(artifacts.join(&file)))?;
+        }
+        write_checksums(&artifacts)
+    }
+
+    fn environment(&self) -> BuildEnvironment {
+        BuildEnvironment {
+            optimizer: "local".to_owned(),
+            image: None,
+            rustc_version: build_manifest::rustc_version(None),
+        }
+    }
+
+    fn toolchain(&self) -> String {
+        host_toolchain("local")
+    }
+}
+
+/// A command template from `tooling.optimizer_command`.
+///
+/// `{workspace}` is replaced by the workspace root and a `{contracts}` argument by the directories
+/// of the selected contracts (nothing when building the whole workspace). The template is split
+/// into arguments before the placeholders are replaced, so paths with spaces are passed as-is.
+pub struct CustomOptimizer {
+    pub command: String,
+}
+
+impl Optimizer for CustomOptimizer {
+    fn build(
+        &self,
+        project_root: &Path,
+        contracts: Option<&[ContractCrate]>,
+    ) -> Result<(), WarpError> {
+        let workspace = project_root.display().to_string();
+        let args: Vec<String> = self
+            .command
+            .split_whitespace()
+            .flat_map(|token| match token {
+                "{contracts}" => contract_dirs(project_root, contracts),
+                _ => vec![token.replace("{workspace}", &workspace)],
+            })
+            .collect();
+        let Some((program, args)) = args.split_first() else {
+            return Err(WarpError::InvalidOptimizer(
+                "`optimizer_command` is empty".to_owned(),
+            ));
+        };
+        run(Command::new(program).current_dir(project_root).args(args))
+    }
+
+    fn builds_single_contracts(&self) -> bool {
+        self.command.split_whitespace().any(|x| x == "{contracts}")
+    }
+
+    fn environment(&self) -> BuildEnvironment {
+        BuildEnvironment {
+            optimizer: "custom".to_owned(),
+            image: None,
+            rustc_version: build_manifest::rustc_version(None),
+        }
+    }
+
+    fn toolchain(&self) -> String {
+        host_toolchain(&self.command)
+    }
+}
+
+/// The optimizer image to run, `-arm64` variants of the CosmWasm images are used on ARM hosts
+pub fn docker_image(image: Option<&str>, version: Option<&str>, arch: &str) -> String {
+    let image = image.unwrap_or(DEFAULT_OPTIMIZER_IMAGE);
+    // A tag in the image name wins over `optimizer_version`
+    let (image, tag) = match image.rsplit_once(':').filter(|(_, tag)| !tag.contains('/')) {
+        Some((image, tag)) => (image, tag),
+        None => (image, version.unwrap_or(DEFAULT_OPTIMIZER_VERSION)),
+    };
+    if arch == "aarch64" && image.starts_with("cosmwasm/") && !image.ends_with("-arm64") {
+        format!("{image}-arm64:{tag}")
+    } else {
+        format!("{image}:{tag}")
+    }
+}
+
+/// Removes the architecture suffixes from the artifact names and from `checksums.txt`, so every
+/// backend produces `artifacts/<name>.wasm`
+pub fn normalize_artifacts(project_root: &Path) -> Result<(), WarpError> {
+    let artifacts = project_root.join("artifacts");
+    if !artifacts.exists() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(&artifacts)?.filter_map(|x| x.ok()) {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if let Some(name) = normalized_name(&file_name) {
+            std::fs::rename(entry.path(), artifacts.join(name))?;
+        }
+    }
+    let checksums = artifacts.join("checksums.txt");
+    if checksums.exists() {
+        let content = std::fs::read_to_string(&checksums)?
+            .lines()
+            .map(|line| match line.split_once(char::is_whitespace) {
+                Some((checksum, name)) => match normalized_name(name.trim()) {
+                    Some(name) => format!("{checksum}  {name}"),
+                    None => line.to_owned(),
+                },
+                None => line.to_owned(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        std::fs::write(checksums, content + "\n")?;
+    }
+    Ok(())
+}
+
+/// `my_contract.wasm` for `my_contract-x86_64.wasm`, `None` if the name has no suffix
+fn normalized_name(file_name: &str) -> Option<String> {
+    let stem = file_name.strip_suffix(".wasm")?;
+    ARCH_SUFFIXES
+        .iter()
+        .find_map(|x| stem.strip_suffix(x))
+        .map(|x| format!("{x}.wasm"))
+}
+
+/// Writes `checksums.txt` for the artifacts, like the optimizer image does
+fn write_checksums(artifacts: &Path) -> Result<(), WarpError> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(artifacts)?
+        .filter_map(|x| x.ok())
+        .map(|x| x.path())
+        .filter(|x| x.extension().is_some_and(|x| x == "wasm"))
+        .collect();
+    files.sort();
+    let mut content = String::new();
+    for file in files {
+        content.push_str(&format!(
+            "{}  {}\n",
+            hex::encode(file_util::sha256_file(&file)?),
+            file.file_name().unwrap_or_default().to_string_lossy()
+        ));
+    }
+    std::fs::write(artifacts.join("checksums.txt"), content)?;
+    Ok(())
+}
+
+/// The optimizer with the host's `rustc` version
+fn host_toolchain(optimizer: &str) -> String {
+    format!(
+        "{} {}",
+        optimizer,
+        build_manifest::rustc_version(None).unwrap_or_default()
+    )
+}
+
+/// `./contracts/<dir>` for each contract, nothing for the whole workspace
+fn contract_dirs(project_root: &Path, contracts: Option<&[ContractCrate]>) -> Vec<String> {
+    contracts
+        .into_iter()
+        .flatten()
+        .map(|x| {
+            let dir = x.dir.strip_prefix(project_root).unwrap_or(&x.dir);
+            format!("./{}", dir.display())
+        })
+        .collect()
+}
+
+fn run(command: &mut Command) -> Result<(), WarpError> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let status = match command.spawn() {
+        Ok(mut child) => child.wait()?,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            return Err(WarpError::InvalidOptimizer(format!(
+                "'{program}' not found, is it installed and in $PATH?"
+            )))
+        }
+        Err(e) => return Err(e.into()),
+    };
+    if !status.success() {
+        return Err(WarpError::OptimizerFailed(program, status));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_docker_image() {
+        assert_eq!(
+            docker_image(None, None, "x86_64"),
+            "cosmwasm/optimizer:0.16.0"
+        );
+        assert_eq!(
+            docker_image(None, Some("0.17.0"), "aarch64"),
+            "cosmwasm/optimizer-arm64:0.17.0"
+        );
+        assert_eq!(
+            docker_image(
+                Some("cosmwasm/workspace-optimizer-arm64:0.12.13"),
+                None,
+                "aarch64"
+            ),
+            "cosmwasm/workspace-optimizer-arm64:0.12.13"
+        );
+        assert_eq!(
+            docker_image(
+                Some("registry.local:5000/optimizer"),
+                Some("1.0"),
+                "aarch64"
+            ),
+            "registry.local:5000/optimizer:1.0"
+        );
+    }
+
+    #[test]
+    fn normalizes_artifact_names() {
+        assert_eq!(normalized_name("cw20-x86_64.wasm").unwrap(), "cw20.wasm");
+        assert_eq!(normalized_name("cw20-aarch64.wasm").unwrap(), "cw20.wasm");
+        assert!(normalized_name("cw20.wasm").is_none());
+        assert!(normalized_name("checksums.txt").is_none());
+    }
+}
@@ -1,11 +1,16 @@
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs::{self, File};
 use std::io::Write;
-use std::path::PathBuf;
+use std::num::NonZeroU64;
+use std::path::{Path, PathBuf};
 
-use crate::error::WarpError;
+use crate::{error::WarpError, utils::keystore};
 
 pub const CONFIG_FILENAME: &str = "Warp.toml";
+pub const DEFAULT_GAS_ADJUSTMENT: f64 = 1.4;
+pub const DEFAULT_STORE_GAS_ADJUSTMENT: f64 = 2.0;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ProjectConfig {
@@ -13,9 +18,27 @@ pub struct ProjectConfig {
     pub tooling: Tooling,
     pub tests: TestConfig,
     pub autodeploy: AutoDeploy,
+    /// Keyring used for signing (defaults to the CLI's own defaults)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keyring: Option<Keyring>,
+    /// Named environments that can be selected with `--env` (or `WARP_ENV`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub environments: BTreeMap<String, Environment>,
+    /// Per-contract settings, by package or artifact name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub contracts: BTreeMap<String, ContractSettings>,
+    /// Settings of `warp codegen`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codegen: Option<CodegenSettings>,
+    /// The environment applied on top of this config, if any
+    #[serde(skip)]
+    pub active_environment: Option<String>,
+    /// Write unsigned transactions to this file instead of broadcasting them
+    #[serde(skip)]
+    pub generate_only: Option<PathBuf>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct Network {
     /// This setting determines which blockchain the workspace is targetting
     pub profile: String,
@@ -23,12 +46,111 @@ pub struct Network {
     pub rpc_url: String,
     pub denom: String,
     pub gas_prices: Option<String>,
+    /// How contract code is uploaded (`direct` by default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upload_via: Option<UploadVia>,
+    /// Deposit attached to code upload proposals, e.g. `1000000000aconst`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proposal_deposit: Option<String>,
+    /// Gas settings for all transactions on this network
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas: Option<GasSettings>,
+    /// How transactions are awaited after broadcasting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmation: Option<ConfirmationSettings>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ConfirmationSettings {
+    /// Seconds to wait for a transaction to be included (60 by default)
+    pub timeout: Option<u64>,
+    /// Milliseconds before the first lookup, doubled after each miss (500 by default)
+    pub poll_interval: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct GasSettings {
+    /// Fixed gas limit instead of simulating the transaction (`--gas auto`)
+    pub limit: Option<u64>,
+    /// Gas adjustment for simulated transactions (1.4 by default)
+    pub adjustment: Option<f64>,
+    /// Gas adjustment for code uploads (2 by default)
+    pub store_adjustment: Option<f64>,
+    /// How many times a transaction that ran out of gas is retried with more gas (2 by default)
+    pub retries: Option<u32>,
+}
+
+impl GasSettings {
+    /// Settings of `other` take precedence
+    pub fn merge(&self, other: &GasSettings) -> GasSettings {
+        GasSettings {
+            limit: other.limit.or(self.limit),
+            adjustment: other.adjustment.or(self.adjustment),
+            store_adjustment: other.store_adjustment.or(self.store_adjustment),
+            retries: other.retries.or(self.retries),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadVia {
+    /// `tx wasm store` from the deployment account
+    Direct,
+    /// A governance proposal, for chains with permissioned code upload
+    Proposal,
+}
+
+/// Per-environment overrides of the base project configuration
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Environment {
+    pub network: Option<Network>,
+    pub account_id: Option<String>,
+    pub gas_prices: Option<String>,
+    /// Step ID -> init message that replaces the step's `init_msg`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub init_msgs: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Keyring {
+    /// `test`, `file`, `os` or `native` (keys kept by Warp itself)
+    pub backend: Option<String>,
+    /// Directory of the keyring (`--keyring-dir`), for `native` relative to the workspace
+    /// (`.warp/keys` by default)
+    pub home: Option<String>,
+    /// File containing the keyring password, relative to the workspace root
+    pub password_file: Option<String>,
+    /// Shell command that prints the keyring password, e.g. `pass show warp/deployer`
+    pub password_command: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct ContractSettings {
+    /// Size budget of the optimized artifact in bytes, `warp build` fails when it's exceeded
+    pub max_size: Option<NonZeroU64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct CodegenSettings {
+    /// Output directory of `warp codegen ts`, relative to the workspace (`tests/src/contracts`)
+    pub ts_out: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct Tooling {
-    /// Backend for optimizing contracts for production
+    /// Backend for optimizing contracts for production: `default` (docker), `cw-optimizoor`,
+    /// `local` or `custom`
     pub optimizer_backend: String,
+    /// Docker image of the `default` backend (`cosmwasm/optimizer`, `-arm64` on ARM hosts)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimizer_image: Option<String>,
+    /// Tag of the optimizer image
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimizer_version: Option<String>,
+    /// Command of the `custom` backend, e.g. `./scripts/optimize.sh {workspace} {contracts}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimizer_command: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -42,9 +164,32 @@ pub struct TestConfig {
 pub struct AutoDeploy {
     pub account_id: String,
     pub make_labels_unique: bool,
+    /// Template for on-chain labels, e.g. `{label}-{git_short_sha}-{env}` or `{label}-v{crate_version}`
+    pub label_template: Option<String>,
+    /// Files that are exported after every deployment (see `warp deploy export`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exports: Vec<DeploymentExport>,
     pub steps: Vec<AutoDeployStep>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DeploymentExport {
+    pub format: ExportFormat,
+    /// Output path, relative to the workspace root
+    pub out: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// TypeScript module with typed constants
+    Ts,
+    /// JSON file
+    Json,
+    /// `.env` file with the values of the active network
+    Env,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct AutoDeployStep {
     pub id: String,
@@ -54,6 +199,64 @@ pub struct AutoDeployStep {
     pub migrate_msg: Option<String>,
     pub init_msg: String,
     pub coins: Option<String>,
+    /// Contract admin: `deployer` (default), `none`, an address or a reference like `$_dao`
+    pub admin: Option<String>,
+    /// Instantiate with `instantiate2` using this salt, which makes the address predictable
+    pub salt: Option<String>,
+    /// Who can instantiate the uploaded code (defaults to the chain's setting)
+    pub instantiate_permission: Option<InstantiatePermission>,
+    /// Gas settings for the transactions of this step (override the network's)
+    pub gas: Option<GasSettings>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum InstantiatePermission {
+    Everybody,
+    Nobody,
+    AnyOf(Vec<String>),
+}
+
+impl InstantiatePermission {
+    pub fn cli_args(&self) -> Vec<String> {
+        match self {
+            InstantiatePermission::Everybody => {
+                vec!["--instantiate-everybody".to_owned(), "true".to_owned()]
+            }
+            InstantiatePermission::Nobody => {
+                vec!["--instantiate-nobody".to_owned(), "true".to_owned()]
+            }
+            InstantiatePermission::AnyOf(addresses) => vec![
+                "--instantiate-anyof-addresses".to_owned(),
+                addresses.join(","),
+            ],
+        }
+    }
+
+    /// The `AccessConfig` of the permission, as used in (proposal) messages
+    pub fn access_config(&self) -> serde_json::Value {
+        match self {
+            InstantiatePermission::Everybody => {
+                serde_json::json!({ "permission": "ACCESS_TYPE_EVERYBODY", "addresses": [] })
+            }
+            InstantiatePermission::Nobody => {
+                serde_json::json!({ "permission": "ACCESS_TYPE_NOBODY", "addresses": [] })
+            }
+            InstantiatePermission::AnyOf(addresses) => {
+                serde_json::json!({ "permission": "ACCESS_TYPE_ANY_OF_ADDRESSES", "addresses": addresses })
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for InstantiatePermission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstantiatePermission::Everybody => write!(f, "everybody"),
+            InstantiatePermission::Nobody => write!(f, "nobody"),
+            InstantiatePermission::AnyOf(x) => write!(f, "any of [{}]", x.join(", ")),
+        }
+    }
 }
 
 impl ProjectConfig {
@@ -65,9 +268,11 @@ impl ProjectConfig {
                 rpc_url: String::new(),
                 denom: String::new(),
                 gas_prices: None,
+                ..Default::default()
             },
             tooling: Tooling {
                 optimizer_backend: String::new(),
+                ..Default::default()
             },
             tests: TestConfig {
                 node_setup_time: 0,
@@ -77,8 +282,16 @@ impl ProjectConfig {
             autodeploy: AutoDeploy {
                 account_id: String::new(),
                 make_labels_unique: false,
+                label_template: None,
+                exports: vec![],
                 steps: vec![],
             },
+            keyring: None,
+            environments: BTreeMap::new(),
+            contracts: BTreeMap::new(),
+            codegen: None,
+            active_environment: None,
+            generate_only: None,
         }
     }
     pub fn generate_and_save(path: PathBuf, network: Network) -> Result<(), WarpError> {
@@ -90,6 +303,7 @@ impl ProjectConfig {
             network,
             tooling: Tooling {
                 optimizer_backend: "default".to_owned(),
+                ..Default::default()
             },
             tests: TestConfig {
                 node_setup_time: 8,
@@ -102,8 +316,16 @@ impl ProjectConfig {
             autodeploy: AutoDeploy {
                 account_id: "dev".to_owned(),
                 make_labels_unique: true,
+                label_template: None,
+                exports: vec![],
                 steps: vec![],
             },
+            keyring: None,
+            environments: BTreeMap::new(),
+            contracts: BTreeMap::new(),
+            codegen: None,
+            active_environment: None,
+            generate_only: None,
         };
         println!(
             "Project dir: {}",
@@ -150,6 +372,135 @@ impl ProjectConfig {
         }
     }
 
+    /// Applies the overrides of the named environment to this config
+    pub fn apply_environment(&mut self, name: &str) -> Result<(), WarpError> {
+        let env = self
+            .environments
+            .get(name)
+            .cloned()
+            .ok_or_else(|| WarpError::UnknownEnvironment(name.to_owned()))?;
+        if let Some(network) = env.network {
+            self.network = network;
+        }
+        if let Some(account_id) = env.account_id {
+            self.autodeploy.account_id = account_id;
+        }
+        if env.gas_prices.is_some() {
+            self.network.gas_prices = env.gas_prices;
+        }
+        for (id, init_msg) in env.init_msgs {
+            let step = self
+                .autodeploy
+                .steps
+                .iter_mut()
+                .find(|x| x.id == id)
+                .ok_or(WarpError::ContractIdNotFound(id))?;
+            step.init_msg = init_msg;
+        }
+        self.active_environment = Some(name.to_owned());
+        Ok(())
+    }
+
+    /// The key under which deployments are stored in `Deployment.toml`
+    pub fn deployment_key(&self) -> String {
+        self.active_environment
+            .clone()
+            .unwrap_or_else(|| self.network.chain_id.clone())
+    }
+
+    /// The size budget of a contract, by artifact name (e.g. `my_contract`)
+    pub fn max_size(&self, artifact_name: &str) -> Option<u64> {
+        self.contracts
+            .iter()
+            .find(|(name, _)| name.replace('-', "_") == artifact_name)
+            .and_then(|(_, x)| x.max_size)
+            .map(NonZeroU64::get)
+    }
+
+    /// A copy of the config that makes transactions write the unsigned tx to `path`
+    pub fn with_generate_only(&self, path: PathBuf) -> ProjectConfig {
+        let mut config = self.clone();
+        config.generate_only = Some(path);
+        config
+    }
+
+    /// A copy of the config with the gas settings of `step` applied on top of the network's
+    pub fn for_step(&self, step: &AutoDeployStep) -> ProjectConfig {
+        let mut config = self.clone();
+        if let Some(gas) = &step.gas {
+            config.network.gas = Some(config.network.gas.unwrap_or_default().merge(gas));
+        }
+        config
+    }
+
+    /// A copy of the config that uses `factor` times more gas
+    pub fn with_more_gas(&self, factor: f64) -> ProjectConfig {
+        let mut config = self.clone();
+        let gas = config.network.gas.get_or_insert_with(Default::default);
+        gas.limit = gas.limit.map(|x| (x as f64 * factor).ceil() as u64);
+        // Rounded to keep the `--gas-adjustment` flags readable
+        let adjust = |x: f64| (x * factor * 100.0).round() / 100.0;
+        gas.adjustment = Some(adjust(gas.adjustment.unwrap_or(DEFAULT_GAS_ADJUSTMENT)));
+        gas.store_adjustment = Some(adjust(
+            gas.store_adjustment.unwrap_or(DEFAULT_STORE_GAS_ADJUSTMENT),
+        ));
+        config
+    }
+
+    /// `--gas` (and `--gas-adjustment`) flags for transactions
+    pub fn gas_cli_args(&self, store: bool) -> Vec<String> {
+        let gas = self.network.gas.clone().unwrap_or_default();
+        match gas.limit {
+            Some(limit) => vec!["--gas".to_owned(), limit.to_string()],
+            None => vec![
+                "--gas".to_owned(),
+                "auto".to_owned(),
+                "--gas-adjustment".to_owned(),
+                if store {
+                    gas.store_adjustment.unwrap_or(DEFAULT_STORE_GAS_ADJUSTMENT)
+                } else {
+                    gas.adjustment.unwrap_or(DEFAULT_GAS_ADJUSTMENT)
+                }
+                .to_string(),
+            ],
+        }
+    }
+
+    /// Keyring flags for commands that access keys (`tx ...` and `keys ...`)
+    pub fn keyring_cli_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        let project_root = Self::find_project_root().unwrap_or_default();
+        if let Some(dir) = self.native_keystore_dir(&project_root) {
+            // The CLI signs with its own copy of the native keys, see `keystore::sync_cli_keyring`
+            let cli_dir = dir.join(keystore::CLI_KEYRING_DIR);
+            args.push("--keyring-backend".to_owned());
+            args.push("test".to_owned());
+            args.push("--keyring-dir".to_owned());
+            args.push(cli_dir.to_string_lossy().to_string());
+            return args;
+        }
+        if let Some(keyring) = &self.keyring {
+            if let Some(backend) = &keyring.backend {
+                args.push("--keyring-backend".to_owned());
+                args.push(backend.clone());
+            }
+            if let Some(home) = &keyring.home {
+                args.push("--keyring-dir".to_owned());
+                args.push(home.clone());
+            }
+        }
+        args
+    }
+
+    /// Directory of the native keystore, if it's the configured keyring backend
+    pub fn native_keystore_dir(&self, project_root: &Path) -> Option<PathBuf> {
+        let keyring = self.keyring.as_ref()?;
+        if keyring.backend.as_deref() != Some("native") {
+            return None;
+        }
+        Some(project_root.join(keyring.home.as_deref().unwrap_or(keystore::DEFAULT_DIR)))
+    }
+
     pub fn save_project_config(&self) -> Result<(), WarpError> {
         let toml_path = Self::find_project_root()?.join(CONFIG_FILENAME);
         std::fs::write(toml_path, toml::to_string_pretty(self)?)?;
@@ -0,0 +1,621 @@
+//! Docs and OpenAPI specs rendered from the contract schemas. The OpenAPI output is built here
+//! rather than with `cw-swaggy`, which only exposes its generator through its own CLI.
+
+use clap::ValueEnum;
+use serde_json::{json, Map, Value};
+
+use super::{
+    codegen_ts::ts_type,
+    contract_schema::{reference_name, variants, ContractSchema},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum DocsFormat {
+    /// Markdown, e.g. for a repository or a docs site
+    Markdown,
+    /// Standalone HTML pages
+    Html,
+}
+
+impl DocsFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DocsFormat::Markdown => "md",
+            DocsFormat::Html => "html",
+        }
+    }
+}
+
+/// The messages that are documented, by the name of their section
+const MESSAGES: [(&str, &str); 4] = [
+    ("instantiate", "Instantiate"),
+    ("execute", "Execute"),
+    ("query", "Query"),
+    ("migrate", "Migrate"),
+];
+
+/// The documentation of a contract, rendered as Markdown or HTML
+#[derive(Debug, Default)]
+pub struct ContractDocs {
+    pub name: String,
+    pub version: String,
+    /// `Instantiate`, `Execute`, `Query`, `Migrate` and `Types`, if the contract has them
+    pub sections: Vec<(String, Vec<DocItem>)>,
+}
+
+/// A message variant or a type definition
+#[derive(Debug, Default, PartialEq)]
+pub struct DocItem {
+    pub name: String,
+    pub description: Option<String>,
+    /// Example of the JSON sent to the contract, for messages
+    pub message: Option<String>,
+    /// Fields of object types
+    pub fields: Vec<DocField>,
+    /// The type of anything that isn't an object with fields
+    pub type_name: Option<String>,
+    /// Response type, for queries
+    pub returns: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DocField {
+    pub name: String,
+    pub type_name: String,
+    pub required: bool,
+    pub description: Option<String>,
+}
+
+impl ContractDocs {
+    pub fn new(schema: &ContractSchema) -> Self {
+        let mut docs = ContractDocs {
+            name: schema.contract_name.clone(),
+            version: schema.contract_version.clone(),
+            ..Default::default()
+        };
+        let mut definitions = Map::new();
+        for (kind, title) in MESSAGES {
+            let Some(message) = message(schema, kind) else {
+                continue;
+            };
+            collect_definitions(message, &mut definitions);
+            let variants = variants(message);
+            let items = if variants.is_empty() {
+                vec![doc_item(kind.to_owned(), description(message), message)]
+            } else {
+                variants
+                    .iter()
+                    .map(|variant| {
+                        let mut item = match variant.args {
+                            Some(args) => {
+                                let mut item = doc_item(variant.name.clone(), None, args);
+                                // Variants without fields, e.g. `{ "config": {} }`
+                                if item.type_name.as_deref() == Some("Record<string, never>") {
+                                    item.type_name = None;
+                                    item.message =
+                                        Some(format!("{{ \"{}\": {{}} }}", variant.name));
+                                } else {
+                                    item.message =
+                                        Some(format!("{{ \"{}\": {{ ... }} }}", variant.name));
+                                }
+                                item
+                            }
+                            None => DocItem {
+                                name: variant.name.clone(),
+                                message: Some(format!("\"{}\"", variant.name)),
+                                ..Default::default()
+                            },
+                        };
+                        item.description = variant.description.map(|x| x.to_owned());
+                        if kind == "query" {
+                            item.returns = schema
+                                .responses
+                                .as_ref()
+                                .and_then(|x| x.get(&variant.name))
+                                .map(|x| {
+                                    collect_definitions(x, &mut definitions);
+                                    let name = response_type(x);
+                                    // Named responses are documented with the other types
+                                    if x.get("title").and_then(|x| x.as_str()) == Some(&name) {
+                                        definitions
+                                            .entry(name.clone())
+                                            .or_insert_with(|| without_definitions(x));
+                                    }
+                                    name
+                                });
+                        }
+                        item
+                    })
+                    .collect()
+            };
+            docs.sections.push((title.to_owned(), items));
+        }
+        if !definitions.is_empty() {
+            docs.sections.push((
+                "Types".to_owned(),
+                definitions
+                    .iter()
+                    .map(|(name, x)| doc_item(name.clone(), description(x), x))
+                    .collect(),
+            ));
+        }
+        docs
+    }
+
+    pub fn render(&self, format: DocsFormat) -> String {
+        match format {
+            DocsFormat::Markdown => self.markdown(),
+            DocsFormat::Html => self.html(),
+        }
+    }
+
+    fn markdown(&self) -> String {
+        let mut out = format!("# {} {}\n\n", self.name, self.version);
+        for (title, items) in self.sections.iter() {
+            out.push_str(&format!("## {title}\n\n"));
+            for item in items {
+                out.push_str(&format!("### `{}`\n\n", item.name));
+                if let Some(description) = &item.description {
+                    out.push_str(&format!("{description}\n\n"));
+                }
+                if let Some(message) = &item.message {
+                    out.push_str(&format!("Message: `{message}`\n\n"));
+                }
+                if let Some(type_name) = &item.type_name {
+                    out.push_str(&format!("Type: `{type_name}`\n\n"));
+                }
+                if !item.fields.is_empty() {
+                    out.push_str("| Field | Type | Required | Description |\n");
+                    out.push_str("| --- | --- | --- | --- |\n");
+                    for field in item.fields.iter() {
+                        out.push_str(&format!(
+                            "| `{}` | `{}` | {} | {} |\n",
+                            field.name,
+                            field.type_name.replace('|', "\\|"),
+                            if field.required { "yes" } else { "no" },
+                            field
+                                .description
+                                .as_deref()
+                                .unwrap_or_default()
+                                .replace('|', "\\|")
+                                .replace('\n', " ")
+                        ));
+                    }
+                    out.push('\n');
+                }
+                if let Some(returns) = &item.returns {
+                    out.push_str(&format!("Returns: `{returns}`\n\n"));
+                }
+            }
+        }
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+        out
+    }
+
+    fn html(&self) -> String {
+        let types: Vec<&str> = self
+            .sections
+            .iter()
+            .filter(|x| x.0 == "Types")
+            .flat_map(|x| x.1.iter().map(|x| x.name.as_str()))
+            .collect();
+        let title = format!("{} {}", escape(&self.name), escape(&self.version));
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n{STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+        );
+        for (section, items) in self.sections.iter() {
+            out.push_str(&format!("<h2>{section}</h2>\n"));
+            for item in items {
+                out.push_str(&format!(
+                    "<h3 id=\"{}-{}\"><code>{}</code></h3>\n",
+                    section.to_lowercase(),
+                    escape(&item.name),
+                    escape(&item.name)
+                ));
+                if let Some(description) = &item.description {
+                    for paragraph in description.split("\n\n") {
+                        out.push_str(&format!("<p>{}</p>\n", escape(paragraph)));
+                    }
+                }
+                if let Some(message) = &item.message {
+                    out.push_str(&format!(
+                        "<p>Message: <code>{}</code></p>\n",
+                        escape(message)
+                    ));
+                }
+                if let Some(type_name) = &item.type_name {
+                    out.push_str(&format!(
+                        "<p>Type: <code>{}</code></p>\n",
+                        link_types(type_name, &types)
+                    ));
+                }
+                if !item.fields.is_empty() {
+                    out.push_str("<table>\n<tr><th>Field</th><th>Type</th><th>Required</th><th>Description</th></tr>\n");
+                    for field in item.fields.iter() {
+                        out.push_str(&format!(
+                            "<tr><td><code>{}</code></td><td><code>{}</code></td><td>{}</td><td>{}</td></tr>\n",
+                            escape(&field.name),
+                            link_types(&field.type_name, &types),
+                            if field.required { "yes" } else { "no" },
+                            escape(field.description.as_deref().unwrap_or_default())
+                        ));
+                    }
+                    out.push_str("</table>\n");
+                }
+                if let Some(returns) = &item.returns {
+                    out.push_str(&format!(
+                        "<p>Returns: <code>{}</code></p>\n",
+                        link_types(returns, &types)
+                    ));
+                }
+            }
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+const STYLE: &str =
+    "body { font-family: sans-serif; max-width: 960px; margin: 2em auto; padding: 0 1em; }
+code { background: #f3f3f3; padding: 0 .2em; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border: 1px solid #ddd; padding: .4em; text-align: left; vertical-align: top; }
+";
+
+/// The index page, linking to each contract's reference and OpenAPI document
+pub fn render_index(schemas: &[ContractSchema], format: DocsFormat) -> String {
+    let extension = format.extension();
+    match format {
+        DocsFormat::Markdown => {
+            let mut out = "# Contracts\n\n".to_owned();
+            for schema in schemas {
+                out.push_str(&format!(
+                    "- [{0}]({0}.{extension}) {1} ([OpenAPI]({0}.openapi.json))\n",
+                    schema.contract_name, schema.contract_version
+                ));
+            }
+            out
+        }
+        DocsFormat::Html => {
+            let mut out = format!("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Contracts</title>\n<style>\n{STYLE}</style>\n</head>\n<body>\n<h1>Contracts</h1>\n<ul>\n");
+            for schema in schemas {
+                out.push_str(&format!(
+                    "<li><a href=\"{0}.{extension}\">{0}</a> {1} (<a href=\"{0}.openapi.json\">OpenAPI</a>)</li>\n",
+                    escape(&schema.contract_name),
+                    escape(&schema.contract_version)
+                ));
+            }
+            out.push_str("</ul>\n</body>\n</html>\n");
+            out
+        }
+    }
+}
+
+/// An OpenAPI 3.1 document with an operation for each message (`/execute/transfer`), its JSON
+/// as the request body and, for queries, the response schema
+pub fn openapi(schema: &ContractSchema) -> Value {
+    let mut definitions = Map::new();
+    let mut paths = Map::new();
+    for (kind, title) in MESSAGES {
+        let Some(message) = message(schema, kind) else {
+            continue;
+        };
+        collect_definitions(message, &mut definitions);
+        let variants = variants(message);
+        if variants.is_empty() {
+            paths.insert(
+                format!("/{kind}"),
+                operation(kind, kind, description(message), message, None),
+            );
+            continue;
+        }
+        for variant in variants.iter() {
+            let body = match variant.args {
+                Some(args) => json!({
+                    "type": "object",
+                    "required": [variant.name],
+                    "properties": { variant.name.clone(): args },
+                    "additionalProperties": false,
+                }),
+                None => json!({ "type": "string", "enum": [variant.name] }),
+            };
+            let response = match kind {
+                "query" => schema.responses.as_ref().and_then(|x| x.get(&variant.name)),
+                _ => None,
+            };
+            if let Some(response) = response {
+                collect_definitions(response, &mut definitions);
+            }
+            paths.insert(
+                format!("/{kind}/{}", variant.name),
+                operation(
+                    title,
+                    &format!("{kind}_{}", variant.name),
+                    variant.description,
+                    &body,
+                    response,
+                ),
+            );
+        }
+    }
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": schema.contract_name,
+            "version": schema.contract_version,
+            "description": format!("Messages of the `{}` contract. The request body of each operation is the JSON message sent to the contract.", schema.contract_name),
+        },
+        "tags": MESSAGES.iter().map(|(_, title)| json!({ "name": title })).collect::<Vec<_>>(),
+        "paths": paths,
+        "components": { "schemas": with_component_refs(&Value::Object(definitions)) },
+    })
+}
+
+fn operation(
+    tag: &str,
+    operation_id: &str,
+    description: Option<&str>,
+    body: &Value,
+    response: Option<&Value>,
+) -> Value {
+    let mut operation = json!({
+        "tags": [tag],
+        "operationId": operation_id,
+        "requestBody": {
+            "required": true,
+            "content": { "application/json": { "schema": with_component_refs(&without_definitions(body)) } },
+        },
+        "responses": { "200": { "description": "Success" } },
+    });
+    if let Some(description) = description {
+        operation["summary"] = json!(description.lines().next().unwrap_or_default());
+        operation["description"] = json!(description);
+    }
+    if let Some(response) = response {
+        operation["responses"]["200"]["content"] = json!({ "application/json": { "schema": with_component_refs(&without_definitions(response)) } });
+    }
+    json!({ "post": operation })
+}
+
+fn message<'a>(schema: &'a ContractSchema, kind: &str) -> Option<&'a Value> {
+    match kind {
+        "instantiate" => schema.instantiate.as_ref(),
+        "execute" => schema.execute.as_ref(),
+        "query" => schema.query.as_ref(),
+        "migrate" => schema.migrate.as_ref(),
+        _ => None,
+    }
+}
+
+fn description(schema: &Value) -> Option<&str> {
+    schema.get("description").and_then(|x| x.as_str())
+}
+
+fn doc_item(name: String, description: Option<&str>, schema: &Value) -> DocItem {
+    let properties = schema.get("properties").and_then(|x| x.as_object());
+    let Some(properties) = properties.filter(|x| !x.is_empty()) else {
+        return DocItem {
+            name,
+            description: description.map(|x| x.to_owned()),
+            type_name: Some(inline_type(schema)),
+            ..Default::default()
+        };
+    };
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(|x| x.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|x| x.as_str())
+        .collect();
+    DocItem {
+        name,
+        description: description.map(|x| x.to_owned()),
+        fields: properties
+            .iter()
+            .map(|(name, x)| DocField {
+                name: name.clone(),
+                type_name: inline_type(x),
+                required: required.contains(&name.as_str()),
+                description: x
+                    .get("description")
+                    .and_then(|x| x.as_str())
+                    .map(|x| x.to_owned()),
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// The type of a query response, named after its schema's title unless it's a generic type like
+/// `Array_of_Addr`
+fn response_type(schema: &Value) -> String {
+    match schema.get("title").and_then(|x| x.as_str()) {
+        Some(title)
+            if !title.contains('_')
+                && (schema.get("properties").is_some() || schema.get("oneOf").is_some()) =>
+        {
+            title.to_owned()
+        }
+        _ => inline_type(schema),
+    }
+}
+
+/// The type on a single line, e.g. `{ amount: Uint128; recipient: string; }`
+fn inline_type(schema: &Value) -> String {
+    ts_type(schema, 0)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Adds the definitions of a message or response, e.g. `Uint128`
+fn collect_definitions(schema: &Value, definitions: &mut Map<String, Value>) {
+    for (name, definition) in schema
+        .get("definitions")
+        .and_then(|x| x.as_object())
+        .into_iter()
+        .flatten()
+    {
+        definitions
+            .entry(name.clone())
+            .or_insert_with(|| definition.clone());
+    }
+}
+
+/// The schema without the draft-07 `definitions`, which are collected separately
+fn without_definitions(schema: &Value) -> Value {
+    let mut schema = schema.clone();
+    if let Some(object) = schema.as_object_mut() {
+        object.remove("definitions");
+        object.remove("$schema");
+    }
+    schema
+}
+
+/// Points `#/definitions/X` references to `#/components/schemas/X`
+fn with_component_refs(schema: &Value) -> Value {
+    match schema {
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(key, value)| match reference_name(schema) {
+                    Some(name) if key == "$ref" => {
+                        (key.clone(), json!(format!("#/components/schemas/{name}")))
+                    }
+                    _ => (key.clone(), with_component_refs(value)),
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(with_component_refs).collect()),
+        _ => schema.clone(),
+    }
+}
+
+/// Escapes the type and links the names of the documented types to their definitions
+fn link_types(type_name: &str, types: &[&str]) -> String {
+    let mut out = String::new();
+    let mut word = String::new();
+    for c in type_name.chars().chain(std::iter::once(' ')) {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            word.push(c);
+            continue;
+        }
+        if types.contains(&word.as_str()) {
+            out.push_str(&format!("<a href=\"#types-{word}\">{word}</a>"));
+        } else {
+            out.push_str(&word);
+        }
+        word.clear();
+        out.push_str(&escape(&c.to_string()));
+    }
+    out.pop();
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    fn schema() -> ContractSchema {
+        ContractSchema {
+            contract_name: "counter".to_owned(),
+            contract_version: "0.1.0".to_owned(),
+            execute: Some(json!({
+                "title": "ExecuteMsg",
+                "oneOf": [
+                    { "type": "string", "enum": ["increment"] },
+                    {
+                        "description": "Resets the counter",
+                        "type": "object",
+                        "required": ["reset"],
+                        "properties": { "reset": {
+                            "type": "object",
+                            "required": ["count"],
+                            "properties": { "count": { "$ref": "#/definitions/Uint128" } }
+                        }}
+                    }
+                ],
+                "definitions": { "Uint128": { "description": "A string-encoded integer", "type": "string" } }
+            })),
+            query: Some(json!({
+                "title": "QueryMsg",
+                "oneOf": [{
+                    "type": "object",
+                    "required": ["get_count"],
+                    "properties": { "get_count": { "type": "object" } }
+                }]
+            })),
+            responses: Some(BTreeMap::from([(
+                "get_count".to_owned(),
+                json!({
+                    "title": "GetCountResponse",
+                    "type": "object",
+                    "required": ["count"],
+                    "properties": { "count": { "$ref": "#/definitions/Uint128" } },
+                    "definitions": { "Uint128": { "type": "string" } }
+                }),
+            )])),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn documents_messages_and_types() {
+        let docs = ContractDocs::new(&schema());
+        let sections: Vec<&str> = docs.sections.iter().map(|x| x.0.as_str()).collect();
+        assert_eq!(sections, ["Execute", "Query", "Types"]);
+        let types: Vec<&str> = docs.sections[2].1.iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(types, ["GetCountResponse", "Uint128"]);
+        assert_eq!(docs.sections[2].1[0].fields[0].type_name, "Uint128");
+        let reset = &docs.sections[0].1[1];
+        assert_eq!(reset.description.as_deref(), Some("Resets the counter"));
+        assert_eq!(reset.fields[0].type_name, "Uint128");
+        assert!(reset.fields[0].required);
+        assert_eq!(
+            docs.sections[1].1[0].returns.as_deref(),
+            Some("GetCountResponse")
+        );
+        let markdown = docs.render(DocsFormat::Markdown);
+        assert!(markdown.contains("### `increment`\n\nMessage: `\"increment\"`\n\n### `reset`"));
+        assert!(
+            markdown.contains("Message: `{ \"get_count\": {} }`\n\nReturns: `GetCountResponse`")
+        );
+        assert!(markdown.contains("| `count` | `Uint128` | yes |  |\n"));
+        let html = docs.render(DocsFormat::Html);
+        assert!(html.contains("<code><a href=\"#types-Uint128\">Uint128</a></code>"));
+    }
+
+    #[test]
+    fn openapi_refers_to_components() {
+        let api = openapi(&schema());
+        let reset = &api["paths"]["/execute/reset"]["post"];
+        assert_eq!(reset["summary"], "Resets the counter");
+        assert_eq!(
+            reset["requestBody"]["content"]["application/json"]["schema"]["properties"]["reset"]
+                ["properties"]["count"]["$ref"],
+            "#/components/schemas/Uint128"
+        );
+        let get_count = &api["paths"]["/query/get_count"]["post"];
+        assert_eq!(
+            get_count["responses"]["200"]["content"]["application/json"]["schema"]["title"],
+            "GetCountResponse"
+        );
+        assert!(
+            get_count["responses"]["200"]["content"]["application/json"]["schema"]
+                .get("definitions")
+                .is_none()
+        );
+        assert_eq!(api["components"]["schemas"]["Uint128"]["type"], "string");
+    }
+}
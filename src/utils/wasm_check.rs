@@ -0,0 +1,327 @@
+use std::collections::BTreeSet;
+
+use wasmparser::{
+    CompositeInnerType, Operator, Parser, Payload, TypeRef, ValType, Validator, WasmFeatures,
+};
+
+use crate::error::WarpError;
+
+/// Largest contract wasmd accepts by default (`MaxWasmSize`)
+pub const DEFAULT_MAX_WASM_SIZE: usize = 800 * 1024;
+
+const REQUIRED_EXPORTS: [&str; 4] = ["instantiate", "allocate", "deallocate", "interface_version_8"];
+
+/// Functions the CosmWasm VM provides to contracts (module `env`)
+const SUPPORTED_IMPORTS: [&str; 24] = [
+    "db_read",
+    "db_write",
+    "db_remove",
+    "db_scan",
+    "db_next",
+    "db_next_key",
+    "db_next_value",
+    "addr_validate",
+    "addr_canonicalize",
+    "addr_humanize",
+    "secp256k1_verify",
+    "secp256k1_recover_pubkey",
+    "secp256r1_verify",
+    "secp256r1_recover_pubkey",
+    "ed25519_verify",
+    "ed25519_batch_verify",
+    "bls12_381_aggregate_g1",
+    "bls12_381_aggregate_g2",
+    "bls12_381_pairing_equality",
+    "bls12_381_hash_to_g1",
+    "bls12_381_hash_to_g2",
+    "debug",
+    "abort",
+    "query_chain",
+];
+
+/// CosmWasm versions that can be required with `requires_cosmwasm_<major>_<minor>`, in order
+const COSMWASM_VERSIONS: [&str; 7] = [
+    "cosmwasm_1_1",
+    "cosmwasm_1_2",
+    "cosmwasm_1_3",
+    "cosmwasm_1_4",
+    "cosmwasm_2_0",
+    "cosmwasm_2_1",
+    "cosmwasm_2_2",
+];
+
+/// Capabilities implied by imports, for contracts that don't export `requires_<capability>`
+const IMPORT_CAPABILITIES: [(&str, &str); 17] = [
+    ("db_scan", "iterator"),
+    ("db_next", "iterator"),
+    ("db_next_key", "iterator"),
+    ("db_next_value", "iterator"),
+    ("secp256r1_verify", "cosmwasm_2_1"),
+    ("secp256r1_recover_pubkey", "cosmwasm_2_1"),
+    ("bls12_381_aggregate_g1", "cosmwasm_2_1"),
+    ("bls12_381_aggregate_g2", "cosmwasm_2_1"),
+    ("bls12_381_pairing_equality", "cosmwasm_2_1"),
+    ("bls12_381_hash_to_g1", "cosmwasm_2_1"),
+    ("bls12_381_hash_to_g2", "cosmwasm_2_1"),
+    // Secret Network's fork of the VM
+    ("canonicalize_address", "secret"),
+    ("humanize_address", "secret"),
+    ("secp256k1_sign", "secret"),
+    ("ed25519_sign", "secret"),
+    ("check_gas", "secret"),
+    ("gas_evaporate", "secret"),
+];
+
+/// What the chains of a profile accept
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainCapabilities {
+    pub capabilities: BTreeSet<String>,
+    pub max_wasm_size: usize,
+}
+
+impl ChainCapabilities {
+    /// `capabilities` plus every CosmWasm version up to `cosmwasm` (e.g. `cosmwasm_1_4`)
+    pub fn new(capabilities: &[&str], cosmwasm: &str, max_wasm_size: usize) -> Self {
+        let versions = COSMWASM_VERSIONS
+            .iter()
+            .take(COSMWASM_VERSIONS.iter().position(|x| *x == cosmwasm).map_or(0, |x| x + 1));
+        Self {
+            capabilities: capabilities
+                .iter()
+                .chain(versions)
+                .map(|x| x.to_string())
+                .collect(),
+            max_wasm_size,
+        }
+    }
+
+    /// The newest CosmWasm version the chain supports, e.g. `cosmwasm_1_4`
+    pub fn cosmwasm_version(&self) -> &str {
+        COSMWASM_VERSIONS
+            .iter()
+            .rev()
+            .find(|x| self.capabilities.contains(**x))
+            .copied()
+            .unwrap_or("cosmwasm_1_0")
+    }
+}
+
+/// Result of checking a contract the way `cosmwasm-check` does
+#[derive(Clone, Debug, Default)]
+pub struct WasmReport {
+    pub size: usize,
+    /// Capabilities the contract requires (`requires_<capability>` exports and imports)
+    pub capabilities: BTreeSet<String>,
+    /// Problems that make the chain reject the contract
+    pub errors: Vec<String>,
+}
+
+impl WasmReport {
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Checks the contract against the capabilities of a chain (`chain_name` is used in the errors)
+pub fn check_wasm(
+    wasm: &[u8],
+    chain: &ChainCapabilities,
+    chain_name: &str,
+) -> Result<WasmReport, WarpError> {
+    let mut report = WasmReport {
+        size: wasm.len(),
+        ..Default::default()
+    };
+    if wasm.len() > chain.max_wasm_size {
+        report.errors.push(format!(
+            "The contract is {} bytes, {} accepts at most {} bytes",
+            wasm.len(),
+            chain_name,
+            chain.max_wasm_size
+        ));
+    }
+
+    let mut exports = BTreeSet::new();
+    // Each problem is only reported once, not for every function
+    let mut bulk_memory = false;
+    let mut reference_types = false;
+    let mut tables = 0;
+    for payload in Parser::new(0).parse_all(wasm) {
+        match payload? {
+            Payload::TypeSection(reader) => {
+                for group in reader {
+                    for ty in group?.into_types() {
+                        let CompositeInnerType::Func(func) = &ty.composite_type.inner else {
+                            continue;
+                        };
+                        if func
+                            .params()
+                            .iter()
+                            .chain(func.results())
+                            .any(|x| matches!(x, ValType::Ref(_)))
+                        {
+                            reference_types = true;
+                        }
+                    }
+                }
+            }
+            Payload::ImportSection(reader) => {
+                for import in reader {
+                    let import = import?;
+                    let implied = IMPORT_CAPABILITIES.iter().find(|(name, _)| *name == import.name);
+                    if let (Some((_, capability)), "env") = (implied, import.module) {
+                        report.capabilities.insert(capability.to_string());
+                    }
+                    match import.ty {
+                        TypeRef::Func(_)
+                            if import.module == "env"
+                                && (SUPPORTED_IMPORTS.contains(&import.name) || implied.is_some()) => {}
+                        TypeRef::Table(_) => tables += 1,
+                        _ => report.errors.push(format!(
+                            "Unsupported import: {}.{}",
+                            import.module, import.name
+                        )),
+                    }
+                }
+            }
+            Payload::TableSection(reader) => tables += reader.count(),
+            Payload::ExportSection(reader) => {
+                for export in reader {
+                    exports.insert(export?.name.to_owned());
+                }
+            }
+            Payload::CodeSectionEntry(body) => {
+                for op in body.get_operators_reader()? {
+                    match op? {
+                        Operator::MemoryCopy { .. }
+                        | Operator::MemoryFill { .. }
+                        | Operator::MemoryInit { .. }
+                        | Operator::DataDrop { .. }
+                        | Operator::TableCopy { .. }
+                        | Operator::TableInit { .. }
+                        | Operator::ElemDrop { .. } => bulk_memory = true,
+                        Operator::RefNull { .. }
+                        | Operator::RefIsNull
+                        | Operator::RefFunc { .. }
+                        | Operator::TableGet { .. }
+                        | Operator::TableSet { .. }
+                        | Operator::TableGrow { .. }
+                        | Operator::TableSize { .. }
+                        | Operator::TableFill { .. } => reference_types = true,
+                        _ => (),
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    for export in REQUIRED_EXPORTS {
+        if !exports.contains(export) {
+            report.errors.push(format!("Missing export: {export}"));
+        }
+    }
+    report.capabilities.extend(
+        exports
+            .iter()
+            .filter_map(|x| x.strip_prefix("requires_"))
+            .map(|x| x.to_owned()),
+    );
+    for capability in report.capabilities.iter() {
+        if chain.capabilities.contains(capability) {
+            continue;
+        }
+        report.errors.push(if capability.starts_with("cosmwasm_") {
+            format!(
+                "The contract needs {} but {} supports up to {}",
+                capability,
+                chain_name,
+                chain.cosmwasm_version()
+            )
+        } else {
+            format!("The contract needs {capability}, which {chain_name} doesn't support")
+        });
+    }
+    if uses_floats(wasm) {
+        report
+            .errors
+            .push("Floating point operations are not supported".to_owned());
+    }
+    if bulk_memory {
+        report
+            .errors
+            .push("Bulk memory operations are not supported".to_owned());
+    }
+    if reference_types || tables > 1 {
+        report.errors.push("Reference types are not supported".to_owned());
+    }
+    Ok(report)
+}
+
+/// Whether the contract uses float or SIMD types or instructions, found by validating it
+/// without those features
+fn uses_floats(wasm: &[u8]) -> bool {
+    let features = WasmFeatures::default();
+    // Only a module that is valid with all features can fail because of the disabled ones
+    if Validator::new_with_features(features).validate_all(wasm).is_err() {
+        return false;
+    }
+    let without_floats = features
+        .difference(WasmFeatures::FLOATS | WasmFeatures::SIMD | WasmFeatures::RELAXED_SIMD);
+    Validator::new_with_features(without_floats)
+        .validate_all(wasm)
+        .is_err()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A module that imports `env.foo` and exports it as `requires_iterator`
+    const MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+        0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section: () -> ()
+        0x02, 0x0b, 0x01, 0x03, b'e', b'n', b'v', 0x03, b'f', b'o', b'o', 0x00, 0x00, // imports
+        0x07, 0x15, 0x01, 0x11, b'r', b'e', b'q', b'u', b'i', b'r', b'e', b's', b'_', b'i', b't',
+        b'e', b'r', b'a', b't', b'o', b'r', 0x00, 0x00, // exports
+    ];
+
+    #[test]
+    fn reports_exports_imports_and_capabilities() {
+        let chain = ChainCapabilities::new(&["iterator"], "cosmwasm_1_4", DEFAULT_MAX_WASM_SIZE);
+        let report = check_wasm(MODULE, &chain, "sei-testnet").unwrap();
+        assert!(report.errors.contains(&"Unsupported import: env.foo".to_owned()));
+        assert!(report.errors.contains(&"Missing export: interface_version_8".to_owned()));
+        assert_eq!(report.capabilities, BTreeSet::from(["iterator".to_owned()]));
+
+        let chain = ChainCapabilities::new(&[], "cosmwasm_1_4", 10);
+        let report = check_wasm(MODULE, &chain, "sei-testnet").unwrap();
+        assert!(report.errors[0].contains("sei-testnet accepts at most 10 bytes"));
+        assert!(report
+            .errors
+            .contains(&"The contract needs iterator, which sei-testnet doesn't support".to_owned()));
+    }
+
+    #[test]
+    fn reports_floats() {
+        // (func (result f32) f32.const 0)
+        let module: &[u8] = &[
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+            0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7d, // type section: () -> f32
+            0x03, 0x02, 0x01, 0x00, // function section
+            0x0a, 0x09, 0x01, 0x07, 0x00, 0x43, 0x00, 0x00, 0x00, 0x00, 0x0b, // code
+        ];
+        let float = "Floating point operations are not supported".to_owned();
+        let chain = ChainCapabilities::new(&[], "cosmwasm_1_4", DEFAULT_MAX_WASM_SIZE);
+        assert!(check_wasm(module, &chain, "juno").unwrap().errors.contains(&float));
+        assert!(!check_wasm(MODULE, &chain, "juno").unwrap().errors.contains(&float));
+    }
+
+    #[test]
+    fn cosmwasm_versions_are_cumulative() {
+        let chain = ChainCapabilities::new(&["stargate"], "cosmwasm_1_4", DEFAULT_MAX_WASM_SIZE);
+        assert!(chain.capabilities.contains("cosmwasm_1_1"));
+        assert!(!chain.capabilities.contains("cosmwasm_2_0"));
+        assert_eq!(chain.cosmwasm_version(), "cosmwasm_1_4");
+    }
+}
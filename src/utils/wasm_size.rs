@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use wasmparser::{ExternalKind, KnownCustom, Name, Parser, Payload, TypeRef};
+
+use crate::error::WarpError;
+
+/// What the bytes of a contract are spent on
+#[derive(Clone, Debug, Default)]
+pub struct SizeBreakdown {
+    pub size: usize,
+    /// Section name -> size, largest first
+    pub sections: Vec<(String, usize)>,
+    /// Function name (or `func[<index>]`) -> size of its body, largest first
+    pub functions: Vec<(String, usize)>,
+    /// Data segment (`data[<index>]` and the start of its content) -> size, largest first
+    pub data: Vec<(String, usize)>,
+    /// Names of the exported functions
+    pub exports: Vec<String>,
+}
+
+pub fn size_breakdown(wasm: &[u8]) -> Result<SizeBreakdown, WarpError> {
+    let mut breakdown = SizeBreakdown {
+        size: wasm.len(),
+        ..Default::default()
+    };
+    let mut imported_functions = 0;
+    let mut bodies = vec![];
+    let mut names: HashMap<u32, String> = HashMap::new();
+    let mut exported: HashMap<u32, String> = HashMap::new();
+    for payload in Parser::new(0).parse_all(wasm) {
+        let payload = payload?;
+        // Custom sections are listed by name below
+        if let Some((id, range)) = payload.as_section().filter(|(id, _)| *id != 0) {
+            breakdown
+                .sections
+                .push((section_name(id).to_owned(), range.len()));
+        }
+        match payload {
+            Payload::ImportSection(reader) => {
+                for import in reader {
+                    if let TypeRef::Func(_) = import?.ty {
+                        imported_functions += 1;
+                    }
+                }
+            }
+            Payload::ExportSection(reader) => {
+                for export in reader {
+                    let export = export?;
+                    if export.kind == ExternalKind::Func {
+                        breakdown.exports.push(export.name.to_owned());
+                        exported.insert(export.index, export.name.to_owned());
+                    }
+                }
+            }
+            Payload::CodeSectionEntry(body) => bodies.push(body.range().len()),
+            Payload::DataSection(reader) => {
+                for (i, data) in reader.into_iter().enumerate() {
+                    let data = data?;
+                    breakdown
+                        .data
+                        .push((format!("data[{i}] {}", preview(data.data)), data.data.len()));
+                }
+            }
+            Payload::CustomSection(reader) => {
+                breakdown.sections.push((
+                    format!("custom \"{}\"", reader.name()),
+                    reader.range().len(),
+                ));
+                if let KnownCustom::Name(reader) = reader.as_known() {
+                    for name in reader {
+                        if let Name::Function(map) = name? {
+                            for naming in map {
+                                let naming = naming?;
+                                let name = format!("{:#}", rustc_demangle::demangle(naming.name));
+                                names.insert(naming.index, name);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    // Optimized builds usually strip the name section, exported functions keep their names
+    breakdown.functions = bodies
+        .into_iter()
+        .enumerate()
+        .map(|(i, size)| {
+            let index = imported_functions + i as u32;
+            let name = names
+                .remove(&index)
+                .or_else(|| exported.remove(&index))
+                .unwrap_or_else(|| format!("func[{index}]"));
+            (name, size)
+        })
+        .collect();
+    for list in [
+        &mut breakdown.sections,
+        &mut breakdown.functions,
+        &mut breakdown.data,
+    ] {
+        list.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    }
+    Ok(breakdown)
+}
+
+/// `812 B` or `12.3 KiB`
+pub fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{bytes} B")
+    } else {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    }
+}
+
+/// Change against the previous size, e.g. `+1.2 KiB`, `-300 B` or `new`
+pub fn format_delta(size: u64, previous: Option<u64>) -> String {
+    match previous {
+        None => "new".to_owned(),
+        Some(previous) if previous == size => "±0".to_owned(),
+        Some(previous) if size > previous => format!("+{}", format_size(size - previous)),
+        Some(previous) => format!("-{}", format_size(previous - size)),
+    }
+}
+
+fn section_name(id: u8) -> &'static str {
+    match id {
+        1 => "type",
+        2 => "import",
+        3 => "function",
+        4 => "table",
+        5 => "memory",
+        6 => "global",
+        7 => "export",
+        8 => "start",
+        9 => "element",
+        10 => "code",
+        11 => "data",
+        12 => "data count",
+        13 => "tag",
+        _ => "unknown",
+    }
+}
+
+/// The start of a data segment, with non-printable bytes replaced by `.`
+fn preview(data: &[u8]) -> String {
+    let text: String = data
+        .iter()
+        .take(32)
+        .map(|x| {
+            if x.is_ascii_graphic() || *x == b' ' {
+                *x as char
+            } else {
+                '.'
+            }
+        })
+        .collect();
+    format!("\"{text}\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A module with one function named `foo` (via the name section) and one data segment
+    const MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+        0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section: () -> ()
+        0x03, 0x02, 0x01, 0x00, // function section
+        0x0a, 0x04, 0x01, 0x02, 0x00, 0x0b, // code section: empty body
+        0x0b, 0x07, 0x01, 0x00, 0x41, 0x00, 0x0b, 0x01, b'h', // data section
+        0x00, 0x0d, 0x04, b'n', b'a', b'm', b'e', 0x01, 0x06, 0x01, 0x00, 0x03, b'f', b'o', b'o',
+    ];
+
+    #[test]
+    fn breaks_down_functions_data_and_sections() {
+        let breakdown = size_breakdown(MODULE).unwrap();
+        assert_eq!(breakdown.size, MODULE.len());
+        assert_eq!(breakdown.functions, vec![("foo".to_owned(), 2)]);
+        assert_eq!(breakdown.data, vec![("data[0] \"h\"".to_owned(), 1)]);
+        assert!(breakdown.sections.contains(&("code".to_owned(), 4)));
+        assert!(breakdown
+            .sections
+            .contains(&("custom \"name\"".to_owned(), 13)));
+    }
+
+    #[test]
+    fn formats_size_deltas() {
+        assert_eq!(format_delta(2048, None), "new");
+        assert_eq!(format_delta(2048, Some(2048)), "±0");
+        assert_eq!(format_delta(3584, Some(2048)), "+1.5 KiB");
+        assert_eq!(format_delta(1748, Some(2048)), "-300 B");
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use owo_colors::OwoColorize;
+
+use crate::error::WarpError;
+
+/// How long the files must stay untouched before a change is reported, so that saving many
+/// files at once (or a formatter rewriting them) triggers a single rebuild
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watches `contracts/`, `packages/` and the workspace manifest for changes
+pub struct WorkspaceWatcher {
+    project_root: PathBuf,
+    events: Receiver<notify::Result<Event>>,
+    // Stops watching when dropped
+    _watcher: RecommendedWatcher,
+}
+
+impl WorkspaceWatcher {
+    pub fn new(project_root: &Path) -> Result<Self, WarpError> {
+        let (tx, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        for dir in ["contracts", "packages"] {
+            let path = project_root.join(dir);
+            if path.exists() {
+                watcher.watch(&path, RecursiveMode::Recursive)?;
+            }
+        }
+        // `Cargo.toml` and `Cargo.lock` are replaced rather than modified by some editors
+        watcher.watch(project_root, RecursiveMode::NonRecursive)?;
+        Ok(Self {
+            project_root: project_root.to_owned(),
+            events,
+            _watcher: watcher,
+        })
+    }
+
+    /// Blocks until sources change and returns the changed paths, relative to the workspace
+    pub fn wait(&self) -> Result<Vec<PathBuf>, WarpError> {
+        let mut changed: Vec<PathBuf> = vec![];
+        loop {
+            let event = if changed.is_empty() {
+                self.events
+                    .recv()
+                    .map_err(|_| RecvTimeoutError::Disconnected)
+            } else {
+                self.events.recv_timeout(DEBOUNCE)
+            };
+            match event {
+                Ok(event) => {
+                    let event = event?;
+                    if matches!(event.kind, EventKind::Access(_)) {
+                        continue;
+                    }
+                    for path in event.paths {
+                        let path = path.strip_prefix(&self.project_root).unwrap_or(&path);
+                        if is_source(path) && !changed.iter().any(|x| x == path) {
+                            changed.push(path.to_owned());
+                        }
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => return Ok(changed),
+                Err(RecvTimeoutError::Disconnected) => return Err(WarpError::WatcherStopped),
+            }
+        }
+    }
+}
+
+/// Prints the changed files that triggered an iteration
+pub fn print_changes(changed: &[PathBuf]) {
+    let mut files: Vec<String> = changed
+        .iter()
+        .take(3)
+        .map(|x| x.display().to_string())
+        .collect();
+    if changed.len() > 3 {
+        files.push(format!("and {} more", changed.len() - 3));
+    }
+    println!("\n{} {}", "Changed:".bright_yellow(), files.join(", "));
+}
+
+/// Runs a step of an iteration and prints whether it succeeded and how long it took.
+/// Errors are printed rather than returned, so that the next change gets another try.
+pub fn run_step(name: &str, step: impl FnOnce() -> Result<(), WarpError>) -> bool {
+    let start = Instant::now();
+    let result = step();
+    let elapsed = start.elapsed().as_secs_f32();
+    match result {
+        Ok(()) => {
+            println!(
+                " {} {} ({:.1}s)",
+                name.bright_blue(),
+                "Done.".bright_green(),
+                elapsed
+            );
+            true
+        }
+        Err(e) => {
+            println!(
+                " {} {} {} ({:.1}s)",
+                name.bright_blue(),
+                "Failed:".bright_red(),
+                e,
+                elapsed
+            );
+            false
+        }
+    }
+}
+
+/// Whether a change to the path (relative to the workspace) can affect the contracts
+fn is_source(path: &Path) -> bool {
+    let in_sources = path.starts_with("contracts")
+        || path.starts_with("packages")
+        || path == Path::new("Cargo.toml")
+        || path == Path::new("Cargo.lock");
+    // Build outputs and editor swap/backup files
+    let ignored = path
+        .components()
+        .any(|x| matches!(x.as_os_str().to_str(), Some("target" | "schema" | ".git")))
+        || path.file_name().is_some_and(|x| {
+            let name = x.to_string_lossy();
+            name.ends_with('~') || name.ends_with(".swp") || name.starts_with(".#")
+        });
+    in_sources && !ignored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_sources_trigger_rebuilds() {
+        assert!(is_source(Path::new("contracts/cw20/src/contract.rs")));
+        assert!(is_source(Path::new("packages/common/src/lib.rs")));
+        assert!(is_source(Path::new("Cargo.lock")));
+        assert!(!is_source(Path::new(
+            "contracts/cw20/target/debug/build.log"
+        )));
+        assert!(!is_source(Path::new("contracts/cw20/schema/cw20.json")));
+        assert!(!is_source(Path::new("contracts/cw20/src/.contract.rs.swp")));
+        assert!(!is_source(Path::new("artifacts/cw20.wasm")));
+        assert!(!is_source(Path::new("Warp.toml")));
+    }
+}
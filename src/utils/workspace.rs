@@ -0,0 +1,265 @@
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use sha2::{Digest, Sha256};
+use toml::Value;
+
+use crate::error::WarpError;
+
+/// A contract crate from the `contracts/` directory of the workspace
+#[derive(Clone, Debug)]
+pub struct ContractCrate {
+    pub name: String,
+    pub version: String,
+    pub dir: PathBuf,
+}
+
+impl ContractCrate {
+    /// The artifact name produced by the optimizer (`artifacts/<name>.wasm`)
+    pub fn artifact_name(&self) -> String {
+        self.name.replace('-', "_")
+    }
+
+    /// Matches the package name or the artifact name
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name == name || self.artifact_name() == name
+    }
+}
+
+pub fn contract_crates(project_root: &Path) -> Result<Vec<ContractCrate>, WarpError> {
+    let contracts_dir = project_root.join("contracts");
+    if !contracts_dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut crates = vec![];
+    for entry in std::fs::read_dir(&contracts_dir)?.filter_map(|x| x.ok()) {
+        let manifest_path = entry.path().join("Cargo.toml");
+        if !manifest_path.exists() {
+            continue;
+        }
+        let manifest: Value = toml::from_str(&std::fs::read_to_string(&manifest_path)?)?;
+        let package = match manifest.get("package") {
+            Some(x) => x,
+            None => continue,
+        };
+        let name = package
+            .get("name")
+            .and_then(|x| x.as_str())
+            .unwrap_or_default()
+            .to_owned();
+        let version = match package.get("version") {
+            Some(Value::String(x)) => x.clone(),
+            // `version.workspace = true`
+            Some(_) => workspace_version(project_root)?.unwrap_or_default(),
+            None => String::new(),
+        };
+        crates.push(ContractCrate {
+            name,
+            version,
+            dir: entry.path(),
+        });
+    }
+    crates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(crates)
+}
+
+/// Finds the contract crate that produces the given artifact (e.g. `artifacts/my_contract.wasm`)
+pub fn find_contract_for_artifact(
+    project_root: &Path,
+    artifact: &str,
+) -> Result<Option<ContractCrate>, WarpError> {
+    let stem = Path::new(artifact)
+        .file_stem()
+        .map(|x| x.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok(contract_crates(project_root)?
+        .into_iter()
+        .find(|x| x.artifact_name() == stem))
+}
+
+/// Files outside `contracts/` that every contract depends on
+const SHARED_PATHS: [&str; 3] = ["Cargo.toml", "Cargo.lock", "packages"];
+
+/// Contracts with sources newer than their artifact (all of them if shared code changed)
+pub fn changed_since_build(
+    project_root: &Path,
+    crates: Vec<ContractCrate>,
+    artifact: impl Fn(&ContractCrate) -> PathBuf,
+) -> Result<Vec<ContractCrate>, WarpError> {
+    let shared = SHARED_PATHS
+        .iter()
+        .map(|x| last_modified(&project_root.join(x)))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .max()
+        .flatten();
+    let mut changed = vec![];
+    for contract in crates {
+        let built = match std::fs::metadata(artifact(&contract)) {
+            Ok(x) => x.modified()?,
+            Err(_) => {
+                changed.push(contract);
+                continue;
+            }
+        };
+        let modified = last_modified(&contract.dir)?.max(shared);
+        if modified.is_some_and(|x| x > built) {
+            changed.push(contract);
+        }
+    }
+    Ok(changed)
+}
+
+/// Contracts with files that differ from `changed` (paths relative to the workspace)
+pub fn changed_in_files(
+    crates: Vec<ContractCrate>,
+    project_root: &Path,
+    changed: &[PathBuf],
+) -> Vec<ContractCrate> {
+    let shared_changed = changed.iter().any(|x| {
+        SHARED_PATHS
+            .iter()
+            .any(|shared| x.starts_with(shared))
+    });
+    crates
+        .into_iter()
+        .filter(|contract| {
+            let dir = contract.dir.strip_prefix(project_root).unwrap_or(&contract.dir);
+            shared_changed || changed.iter().any(|x| x.starts_with(dir))
+        })
+        .collect()
+}
+
+/// Newest modification time of the files under `path`, ignoring `target` directories
+fn last_modified(path: &Path) -> Result<Option<SystemTime>, WarpError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    if path.is_file() {
+        return Ok(Some(std::fs::metadata(path)?.modified()?));
+    }
+    let mut newest = None;
+    for entry in std::fs::read_dir(path)?.filter_map(|x| x.ok()) {
+        if entry.file_name() == "target" {
+            continue;
+        }
+        newest = newest.max(last_modified(&entry.path())?);
+    }
+    Ok(newest)
+}
+
+/// Files that pin the toolchain of the workspace
+const TOOLCHAIN_FILES: [&str; 2] = ["rust-toolchain", "rust-toolchain.toml"];
+
+/// Hash of everything an optimized build of the contract depends on: its sources, the shared
+/// code, the lock file, the toolchain files and `toolchain` (which identifies the optimizer)
+pub fn input_hash(
+    project_root: &Path,
+    contract: &ContractCrate,
+    toolchain: &str,
+) -> Result<String, WarpError> {
+    let mut hasher = Sha256::new();
+    hasher.update(toolchain.as_bytes());
+    let paths = std::iter::once(contract.dir.clone()).chain(
+        SHARED_PATHS
+            .iter()
+            .chain(TOOLCHAIN_FILES.iter())
+            .map(|x| project_root.join(x)),
+    );
+    // The generated schema isn't an input
+    let ignored = contract.dir.join("schema");
+    for path in paths {
+        hash_path(&mut hasher, project_root, &path, &ignored)?;
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Hashes the relative paths and contents of the files under `path`, ignoring `target` directories
+fn hash_path(
+    hasher: &mut Sha256,
+    project_root: &Path,
+    path: &Path,
+    ignored: &Path,
+) -> Result<(), WarpError> {
+    if !path.exists() || path == ignored {
+        return Ok(());
+    }
+    if path.is_file() {
+        let content = std::fs::read(path)?;
+        let relative = path.strip_prefix(project_root).unwrap_or(path);
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(content);
+        return Ok(());
+    }
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(path)?
+        .filter_map(|x| x.ok())
+        .filter(|x| x.file_name() != "target" && x.file_name() != ".git")
+        .map(|x| x.path())
+        .collect();
+    entries.sort();
+    for entry in entries {
+        hash_path(hasher, project_root, &entry, ignored)?;
+    }
+    Ok(())
+}
+
+fn workspace_version(project_root: &Path) -> Result<Option<String>, WarpError> {
+    let manifest: Value =
+        toml::from_str(&std::fs::read_to_string(project_root.join("Cargo.toml"))?)?;
+    Ok(manifest
+        .get("workspace")
+        .and_then(|x| x.get("package"))
+        .and_then(|x| x.get("version"))
+        .and_then(|x| x.as_str())
+        .map(|x| x.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contract(name: &str) -> ContractCrate {
+        ContractCrate {
+            name: name.to_owned(),
+            version: "0.1.0".to_owned(),
+            dir: PathBuf::from("/ws/contracts").join(name),
+        }
+    }
+
+    #[test]
+    fn input_hash_ignores_build_outputs() {
+        let root = std::env::temp_dir().join(format!("warp-input-hash-{}", std::process::id()));
+        let contract = ContractCrate {
+            dir: root.join("contracts/cw20"),
+            ..contract("cw20")
+        };
+        std::fs::create_dir_all(contract.dir.join("src")).unwrap();
+        std::fs::create_dir_all(contract.dir.join("target")).unwrap();
+        std::fs::write(contract.dir.join("src/lib.rs"), "pub fn a() {}").unwrap();
+        let hash = input_hash(&root, &contract, "cosmwasm/optimizer:0.16.0").unwrap();
+
+        std::fs::write(contract.dir.join("target/out.wasm"), "wasm").unwrap();
+        assert_eq!(input_hash(&root, &contract, "cosmwasm/optimizer:0.16.0").unwrap(), hash);
+        assert_ne!(input_hash(&root, &contract, "cosmwasm/optimizer:0.17.0").unwrap(), hash);
+        std::fs::write(root.join("Cargo.lock"), "# lock").unwrap();
+        assert_ne!(input_hash(&root, &contract, "cosmwasm/optimizer:0.16.0").unwrap(), hash);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn shared_changes_select_every_contract() {
+        let crates = vec![contract("acl"), contract("factory")];
+        let changed = changed_in_files(
+            crates.clone(),
+            Path::new("/ws"),
+            &[PathBuf::from("contracts/acl/src/lib.rs")],
+        );
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].name, "acl");
+        let changed = changed_in_files(crates, Path::new("/ws"), &[PathBuf::from("Cargo.lock")]);
+        assert_eq!(changed.len(), 2);
+    }
+}